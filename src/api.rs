@@ -1,142 +1,511 @@
-use crate::{signals::Message, App};
-use std::fs;
+use crate::{
+    enums::{CategoryForm, ConnectionState, NotificationLevel, RenameTarget}, magnet::check_link, metainfo::{Metainfo, TorrentPreview}, helpers::{parse_rate, parse_ratio_limit, parse_seeding_time_limit}, notifications::describe_error, signals::Message, sync::merge_peers, 
+    worker::{ApiRequest, ApiResponse, ApiWorker}, App
+};
+use std::{fs, path::Path};
 use std::str::FromStr;
+use std::time::Instant;
 use color_eyre::Result;
-use qbit_rs::{
-    model::{AddTorrentArg, Credential, GetTorrentListArg, Sep, TorrentFile, TorrentFilter, TorrentSource}, 
-    Qbit};
+use qbit_rs::model::{AddTorrentArg, Priority, Sep, TorrentFile, TorrentSource};
+
+/// Returns the file name of a path for the add torrent summary.
+fn file_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map_or(path.to_string(), |name| name.to_string_lossy().to_string())
+}
+
+/// Shorten a magnet link or URL for the add torrent summary.
+fn short_link(link: &str) -> String {
+    const MAX_LENGTH: usize = 60;
+    if link.chars().count() > MAX_LENGTH {
+        format!("{}…", link.chars().take(MAX_LENGTH).collect::<String>())
+    } else {
+        link.to_string()
+    }
+}
 
 impl App {
-    fn api(&self) -> Qbit {
-        let credential =  Credential::new(&self.cfg.username, &self.cfg.password);
-        let url: &str = &self.cfg.api_url;
-        Qbit::new(url, credential)
-    }
-
-    pub async fn get_torrents(&mut self) -> Result<()> {
-        let api = self.api();
-        let arg = GetTorrentListArg {
-            filter: Some(TorrentFilter::All),
-            category: None,
-            tag: None,
-            sort: None,
-            reverse: None,
-            limit: Some(10),
-            offset: None,
-            hashes: None,
-        };
-        let torrents = api.get_torrent_list(arg).await;
-        match torrents {
-            Ok(torrents) => self.torrents = torrents,
-            // TODO: Create a popup with the error message.
-            Err(_err) => {},
+    /// Start a new API worker for the current config, replacing the previous one.
+    /// Responses from requests still in flight on the previous worker are dropped.
+    pub fn start_worker(&mut self) {
+        self.worker = Some(ApiWorker::spawn(self.cfg.clone()));
+        self.pending_requests = 0;
+        self.syncing = false;
+        self.connection = ConnectionState::Connecting;
+        self.refresh_failures = 0;
+        self.request(ApiRequest::Version);
+    }
+
+    /// Queue a request on the API worker.
+    /// The request counts as pending until its response is handled.
+    fn request(&mut self, request: ApiRequest) {
+        if let Some(worker) = &self.worker {
+            worker.send(request);
+            self.pending_requests += 1;
+        }
+    }
+
+    /// Syncs the torrent list using /api/v2/sync/maindata.
+    /// Only the changes since the last response ID are sent by the server.
+    pub fn get_torrents(&mut self) {
+        // Wait for the sync in flight so the response IDs are applied in order.
+        if self.syncing {
+            return;
+        }
+        self.syncing = true;
+        self.request(ApiRequest::Sync { rid: self.torrent_sync.rid() });
+    }
+
+    /// Pause the selected torrents.
+    pub fn pause_torrents(&mut self) {
+        let hashes = self.selected_hashes();
+        if !hashes.is_empty() {
+            self.request(ApiRequest::Pause { hashes });
+        }
+    }
+
+    /// Resume the selected torrents.
+    pub fn resume_torrents(&mut self) {
+        let hashes = self.selected_hashes();
+        if !hashes.is_empty() {
+            self.request(ApiRequest::Resume { hashes });
+        }
+    }
+
+    /// Toggle force start on the selected torrents.
+    /// The new value is the opposite of the highlighted torrent's current force start state.
+    pub fn toggle_force_start(&mut self) {
+        let hashes = self.selected_hashes();
+        if hashes.is_empty() {
+            return;
+        }
+        let force_start = self.state.selected()
+            .and_then(|i| self.torrents.get(i))
+            .and_then(|torrent| torrent.force_start)
+            .unwrap_or(false);
+        self.request(ApiRequest::ForceStart { hashes, value: !force_start });
+    }
+
+    /// Recheck the downloaded data of the selected torrents.
+    pub fn recheck_torrents(&mut self) {
+        let hashes = self.selected_hashes();
+        if !hashes.is_empty() {
+            self.request(ApiRequest::Recheck { hashes });
+        }
+    }
+
+    /// Reannounce the selected torrents to their trackers.
+    pub fn reannounce_torrents(&mut self) {
+        let hashes = self.selected_hashes();
+        if !hashes.is_empty() {
+            self.request(ApiRequest::Reannounce { hashes });
+        }
+    }
+
+    /// Delete the selected torrents, removing their data from disk if delete_files is set.
+    pub fn delete_torrents(&mut self) {
+        let hashes = self.selected_hashes();
+        if !hashes.is_empty() {
+            self.request(ApiRequest::Delete { hashes, delete_files: self.delete_files });
         }
+    }
+
+    /// Parse the torrent limits popup inputs and set them on the selected torrents.
+    pub fn set_torrent_limits(&mut self) -> Result<()> {
+        let hashes = self.selected_hashes();
+        if hashes.is_empty() {
+            return Ok(());
+        }
+        let download = parse_rate(&self.torrent_dl_limit_input)?;
+        let upload = parse_rate(&self.torrent_up_limit_input)?;
+        let ratio = parse_ratio_limit(&self.ratio_limit_input)?;
+        let seeding_time = parse_seeding_time_limit(&self.seeding_time_limit_input)?;
+        self.request(ApiRequest::SetTorrentLimits { hashes, download, upload, ratio, seeding_time });
         Ok(())
     }
 
-    /// Torrent contents is a vector of details about the files in a torrent.
-    pub async fn get_torrent_contents(&mut self) -> Result<()> {
-        let api = self.api();
-        let torrent = self.torrents.get(self.state.selected().unwrap_or(0)).unwrap();
-        let hash = torrent.hash.clone().unwrap();
-        let content = api.get_torrent_contents(hash, None).await;
-        match content {
-            Ok(content) => self.torrent_content = content,
-            Err(err) => {println!("Error getting torrent content {:?}", err) },
+    /// Switch between the normal and alternative global speed limits.
+    pub fn toggle_alt_speed_limits(&mut self) {
+        self.request(ApiRequest::ToggleAltSpeedLimits);
+    }
+
+    /// Parse the speed limits popup inputs and set them as the global speed limits.
+    pub fn set_speed_limits(&mut self) -> Result<()> {
+        let download = parse_rate(&self.download_limit_input)?;
+        let upload = parse_rate(&self.upload_limit_input)?;
+        self.request(ApiRequest::SetSpeedLimits { download, upload });
+        Ok(())
+    }
+
+    /// Set the priority of the selected files in the files tab.
+    pub fn set_file_priority(&mut self, priority: Priority) {
+        let indexes = self.selected_file_indexes();
+        if indexes.is_empty() || self.torrent_content_hash.is_empty() {
+            return;
+        }
+        let hash = self.torrent_content_hash.clone();
+        self.request(ApiRequest::SetFilePriority { hash, indexes, priority });
+    }
+
+    /// Move the data of the selected torrents to the path in the move popup.
+    pub fn move_torrents(&mut self) -> Result<()> {
+        let hashes = self.selected_hashes();
+        if hashes.is_empty() {
+            return Ok(());
         }
+        let location = self.move_input.trim().to_string();
+        if location.is_empty() {
+            return Err(color_eyre::eyre::eyre!("The path can't be empty"));
+        }
+        self.request(ApiRequest::SetLocation { hashes, location });
         Ok(())
     }
 
-    pub async fn get_torrent_trackers(&mut self) -> Result<()> {
-        let api = self.api();
-        let torrent  = self.torrents.get(self.state.selected().unwrap_or(0)).unwrap();
-        let hash = torrent.hash.clone().unwrap();
-        let trackers = api.get_torrent_trackers(hash).await;
-        match trackers {
-            Ok(trackers) => self.torrent_trackers = trackers,
-            Err(_err) => {},
+    /// Set the category picked in the category picker on the selected torrents.
+    pub fn set_category(&mut self) {
+        let hashes = self.selected_hashes();
+        if let Some(category) = self.picked_category()
+            && !hashes.is_empty() {
+            self.request(ApiRequest::SetCategory { hashes, category });
+        }
+    }
+
+    /// Add the category in the categories manager form, or save the new save path of an existing one.
+    pub fn save_category(&mut self) -> Result<()> {
+        let name = self.category_name_input.trim().to_string();
+        let save_path = self.category_path_input.trim().to_string();
+        match self.category_form {
+            Some(CategoryForm::Add) if name.is_empty() => return Err(color_eyre::eyre::eyre!("The category name can't be empty")),
+            Some(CategoryForm::Add) if self.torrent_sync.categories().contains_key(&name) => {
+                return Err(color_eyre::eyre::eyre!("The category {} already exists", name));
+            },
+            Some(CategoryForm::Add) => self.request(ApiRequest::AddCategory { name, save_path }),
+            Some(CategoryForm::Edit) => self.request(ApiRequest::EditCategory { name, save_path }),
+            None => {},
         }
         Ok(())
     }
 
-    pub async fn get_torrent_peers(&mut self) -> Result<()> {
-        let api = self.api();
-        let torrent = self.torrents.get(self.state.selected().unwrap_or(0)).unwrap();
-        let hash = torrent.hash.clone().unwrap();
+    /// Remove the highlighted category in the categories manager.
+    /// Its torrents are left without a category.
+    pub fn remove_category(&mut self) {
+        if let Some(category) = self.highlighted_category() {
+            let names = vec![category.name.clone()];
+            self.request(ApiRequest::RemoveCategories { names });
+        }
+    }
+
+    /// Rename the target of the rename prompt to the entered name.
+    /// Files and folders keep their parent folder.
+    pub fn rename(&mut self) -> Result<()> {
+        let name = self.rename_input.trim().to_string();
+        if name.is_empty() {
+            return Err(color_eyre::eyre::eyre!("The name can't be empty"));
+        }
+        let request = match self.rename_target.clone() {
+            Some(RenameTarget::Torrent { hash }) => ApiRequest::RenameTorrent { hash, name },
+            Some(RenameTarget::File { hash, path }) => {
+                let new_path = RenameTarget::renamed_path(&path, &name);
+                ApiRequest::RenameFile { hash, old_path: path, new_path }
+            },
+            Some(RenameTarget::Folder { hash, path }) => {
+                let new_path = RenameTarget::renamed_path(&path, &name);
+                ApiRequest::RenameFolder { hash, old_path: path, new_path }
+            },
+            None => return Ok(()),
+        };
+        // Nothing to do when the name wasn't changed.
+        let unchanged = match &request {
+            ApiRequest::RenameFile { old_path, new_path, .. } | ApiRequest::RenameFolder { old_path, new_path, .. } => old_path == new_path,
+            _ => false,
+        };
+        if !unchanged {
+            self.request(request);
+        }
+        Ok(())
+    }
+
+    /// Torrent contents is a vector of details about the files in a torrent.
+    pub fn get_torrent_contents(&mut self) {
+        if let Some(hash) = self.selected_hash() {
+            self.request(ApiRequest::TorrentContents { hash });
+        }
+    }
+
+    pub fn get_torrent_trackers(&mut self) {
+        if let Some(hash) = self.selected_hash() {
+            self.request(ApiRequest::TorrentTrackers { hash });
+        }
+    }
+
+    pub fn get_torrent_peers(&mut self) {
+        let Some(hash) = self.selected_hash() else {
+            return;
+        };
         // From the qBittorrent API documentation 5.0:
         // Response ID. If not provided, rid=0 will be assumed. 
         // If the given rid is different from the one of last server reply, 
         // full_update will be true (see the server reply details for more info)
-        let peers = api.get_torrent_peers(hash, None).await;
-        match peers {
-            Ok(peers) => self.torrent_peers = Some(peers),
-            Err(_err) => self.torrent_peers = None,
-        }
-        Ok(())
+        // Only continue from the last rid while the same torrent stays selected.
+        let rid = match &self.torrent_peers {
+            Some(peers) if self.torrent_peers_hash == hash => Some(peers.rid),
+            _ => None,
+        };
+        self.request(ApiRequest::TorrentPeers { hash, rid });
     }
 
-    /// Takes [`App`] magnet_link and passes the magnet link to the API.
-    pub async fn add_torrent_magnet(&mut self) -> Result<Message> {
-        let magnet = self.magnet_link.clone();
-        if magnet.is_empty() {
+    /// Takes the magnet links and URLs in [`App`] magnet_link, separated by whitespace, 
+    /// and passes them to the API in a single request.
+    /// Links that can't be parsed are skipped and listed in the summary once the rest are added.
+    pub fn add_torrent_magnet(&mut self) -> Result<()> {
+        let links: Vec<&str> = self.magnet_link.split_whitespace().collect();
+        if links.is_empty() {
             return Err(color_eyre::eyre::eyre!("Magnet link is empty"));
         }
-        let url = match Sep::from_str(magnet.as_str()) {
-            Ok(url) => url,
+        let mut valid = vec![];
+        let mut names = vec![];
+        let mut skipped = vec![];
+        for link in links {
+            let magnet = match check_link(link) {
+                Ok(magnet) => magnet,
+                Err(err) => {
+                    skipped.push(format!("{} ({})", short_link(link), err));
+                    continue;
+                },
+            };
+            match Sep::<_, '\n'>::from_str(link).map(|urls| TorrentSource::Urls { urls }) {
+                Ok(_) => {
+                    valid.push(link);
+                    names.push(magnet.and_then(|magnet| magnet.name).unwrap_or_else(|| short_link(link)));
+                },
+                Err(_) => skipped.push(format!("{} (invalid link)", short_link(link))),
+            }
+        }
+        if valid.is_empty() {
+            return Err(color_eyre::eyre::eyre!("Invalid magnet link format: {}", skipped.join(", ")));
+        }
+        let urls = match Sep::from_str(&valid.join("\n")) {
+            Ok(urls) => urls,
             Err(_) => return Err(color_eyre::eyre::eyre!("Invalid magnet link format")),
         };
-        let torrent_source = TorrentSource::Urls { urls: url };
-        self.add_torrent(torrent_source).await
+        self.add_torrent(TorrentSource::Urls { urls }, names, skipped, None)
     }
 
-    /// Takes [`App`] torrent_file_path and passes the torrent file to the API.
-    pub async fn add_torrent_file(&mut self) -> Result<Message> {
+    /// Reads and parses [`App`] torrent_file_path to preview it before adding.
+    pub fn preview_torrent_file(&mut self) -> Result<()> {
         let file_path = self.torrent_file_path.clone();
         if file_path.is_empty() {
             return Err(color_eyre::eyre::eyre!("Torrent file path is empty"));
         }
-        // We read and pass the raw file into API
-        let file_data: Vec<u8> =  match fs::read(&file_path) {
+        let data = match fs::read(&file_path) {
             Ok(data) => data,
             Err(_) => return Err(color_eyre::eyre::eyre!("Failed to read torrent file")),
         };
-        let torrent_file = TorrentFile {
-            // Unsure if I should be truncating the path to just the file name but works as is.
-            filename: file_path, 
-            data: file_data,
+        let metainfo = match Metainfo::parse(&data) {
+            Ok(metainfo) => metainfo,
+            Err(err) => return Err(color_eyre::eyre::eyre!("{} is not a valid torrent file: {}", file_name(&file_path), err)),
         };
-        let torrent_source = TorrentSource::TorrentFiles { torrents: vec![torrent_file] };
-        self.add_torrent(torrent_source).await
+        self.torrent_preview = Some(TorrentPreview::new(file_path, data, metainfo));
+        Ok(())
     }
 
-    /// Given a [`TorrentSource`], adds the torrent in qBittorrent.
-    async fn add_torrent(&mut self, source: TorrentSource) -> Result<Message> {
-        let api = self.api();
+    /// Adds the previewed torrent file, or the marked torrent files together in a single request.
+    /// Files that can't be read or aren't torrents are skipped and listed in the summary once the rest are added.
+    pub fn add_torrent_file(&mut self) -> Result<()> {
+        if let Some(preview) = self.torrent_preview.as_ref() {
+            let torrent = TorrentFile { filename: preview.path.clone(), data: preview.data.clone() };
+            let unwanted = preview.unwanted();
+            let unwanted = (!unwanted.is_empty()).then(|| (preview.metainfo.info_hash.clone(), unwanted));
+            let names = vec![preview.metainfo.name.clone()];
+            return self.add_torrent(TorrentSource::TorrentFiles { torrents: vec![torrent] }, names, vec![], unwanted);
+        }
+        if self.marked_torrent_files.is_empty() {
+            return Err(color_eyre::eyre::eyre!("No torrent files are marked"));
+        }
+        let mut torrents = vec![];
+        let mut names = vec![];
+        let mut skipped = vec![];
+        for path in self.marked_torrent_files.iter() {
+            let file_path = path.to_string_lossy().to_string();
+            // We read and pass the raw file into API
+            let data = match fs::read(&file_path) {
+                Ok(data) => data,
+                Err(_) => {
+                    skipped.push(format!("{} (failed to read file)", file_name(&file_path)));
+                    continue;
+                },
+            };
+            match Metainfo::parse(&data) {
+                Ok(metainfo) => {
+                    // Unsure if I should be truncating the path to just the file name but works as is.
+                    torrents.push(TorrentFile { filename: file_path, data });
+                    names.push(metainfo.name);
+                },
+                Err(err) => skipped.push(format!("{} (not a valid torrent file: {})", file_name(&file_path), err)),
+            }
+        }
+        if torrents.is_empty() {
+            return Err(color_eyre::eyre::eyre!("No torrent files could be added: {}", skipped.join(", ")));
+        }
+        self.add_torrent(TorrentSource::TorrentFiles { torrents }, names, skipped, None)
+    }
+
+    /// Given a [`TorrentSource`], adds the torrents in qBittorrent with the options from the add torrent form.
+    /// The names of the torrents and of those skipped before sending are used for the summary.
+    fn add_torrent(
+        &mut self,
+        source: TorrentSource,
+        names: Vec<String>,
+        skipped: Vec<String>,
+        unwanted: Option<(String, Vec<i64>)>,
+    ) -> Result<()> {
+        let options = &self.add_options;
+        // Empty text fields are left for qBittorrent to decide.
+        let text = |value: &str| {
+            let value = value.trim();
+            (!value.is_empty()).then(|| value.to_string())
+        };
+        // Unticked options are sent as false so they override the server defaults.
+        let flag = |value: bool| Some(value.to_string());
+        let limit = |value: &str| -> Result<Option<i64>> {
+            let limit = parse_rate(value)?;
+            Ok((limit > 0).then_some(limit as i64))
+        };
         let torrent = AddTorrentArg {
             source,
-            savepath: None,
+            savepath: text(&options.savepath),
             cookie: None,
-            category: None,
-            tags: None,
-            skip_checking: None,
-            paused: None,
-            root_folder: None,
-            rename: None,
-            up_limit: None,
-            download_limit: None,
+            category: text(&options.category),
+            tags: text(&options.tags),
+            skip_checking: flag(options.skip_checking),
+            paused: flag(options.paused),
+            root_folder: options.content_layout.root_folder(),
+            rename: text(&options.rename),
+            up_limit: limit(&options.upload_limit)?,
+            download_limit: limit(&options.download_limit)?,
             ratio_limit: None,
             seeding_time_limit: None,
             auto_torrent_management: None,
-            sequential_download: None,
-            first_last_piece_priority: None,
+            sequential_download: flag(options.sequential_download),
+            first_last_piece_priority: flag(options.first_last_piece_priority),
         };
-        let result = api.add_torrent(torrent).await;
-        match result {
-            Ok(_) => {
-                return Ok(Message::DisplayAddTorrent);
+        self.request(ApiRequest::AddTorrent { arg: Box::new(torrent), names, skipped, unwanted });
+        Ok(())
+    }
+
+    /// Applies a response from the API worker to [`App`].
+    /// Returns an optional message if a follow up update is needed.
+    pub fn handle_api_response(&mut self, response: ApiResponse) -> Option<Message> {
+        self.pending_requests = self.pending_requests.saturating_sub(1);
+        match response {
+            ApiResponse::Synced(data) => {
+                self.syncing = false;
+                match data {
+                    Ok(data) => {
+                        self.torrent_sync.apply(data);
+                        self.rebuild_torrents();
+                        self.refresh_failures = 0;
+                        // Reconnected, get the server version again before showing it as connected.
+                        if !self.connection.is_connected() && self.connection != ConnectionState::Connecting {
+                            self.connection = ConnectionState::Connecting;
+                            self.request(ApiRequest::Version);
+                        }
+                    },
+                    Err(err) => {
+                        // Only notify on the first failure so background refreshes don't repeat it.
+                        if self.refresh_failures == 0 {
+                            self.notify(NotificationLevel::Error, format!("Failed to refresh torrents: {}", describe_error(&err)));
+                        }
+                        self.refresh_failures = self.refresh_failures.saturating_add(1);
+                        self.connection = ConnectionState::from_error(&err);
+                    },
+                }
+                self.last_refresh = Some(Instant::now());
+            },
+            ApiResponse::Version(result) => {
+                match result {
+                    Ok((version, api_version)) => self.connection = ConnectionState::Connected { version, api_version },
+                    // The sync reports the error so only the state is updated here.
+                    Err(err) => self.connection = ConnectionState::from_error(&err),
+                }
+            },
+            // Responses for a torrent that is no longer selected are stale.
+            ApiResponse::TorrentContents { hash, result } => {
+                match result {
+                    Ok(content) if self.selected_hash().as_ref() == Some(&hash) => {
+                        // The file selection only applies to the torrent it was made in.
+                        if self.torrent_content_hash != hash {
+                            self.file_selection.clear();
+                            self.collapsed_folders.clear();
+                            self.torrent_content_hash = hash;
+                        }
+                        self.file_selection.retain(|i| content.iter().any(|file| file.index == *i));
+                        self.torrent_content = content;
+                        self.update_file_tree();
+                    },
+                    Ok(_) => {},
+                    Err(err) => self.notify(NotificationLevel::Warning, format!("Failed to get torrent files: {}", describe_error(&err))),
+                }
+            },
+            ApiResponse::TorrentTrackers { hash, result } => {
+                match result {
+                    Ok(trackers) if self.selected_hash() == Some(hash) => self.torrent_trackers = trackers,
+                    Ok(_) => {},
+                    Err(err) => self.notify(NotificationLevel::Warning, format!("Failed to get torrent trackers: {}", describe_error(&err))),
+                }
+            },
+            ApiResponse::TorrentPeers { hash, rid, result } => {
+                if self.selected_hash().as_ref() != Some(&hash) {
+                    return None;
+                }
+                match result {
+                    Ok(peers) => {
+                        match self.torrent_peers.as_mut() {
+                            Some(existing) if rid.is_some() => merge_peers(existing, peers),
+                            _ => self.torrent_peers = Some(peers),
+                        }
+                        self.torrent_peers_hash = hash;
+                    },
+                    Err(err) => {
+                        self.torrent_peers = None;
+                        self.notify(NotificationLevel::Warning, format!("Failed to get torrent peers: {}", describe_error(&err)));
+                    },
+                }
+            },
+            ApiResponse::ActionDone { done, failed, result } => {
+                match result {
+                    Ok(_) => self.notify(NotificationLevel::Success, done),
+                    Err(err) => self.notify(NotificationLevel::Error, format!("Failed to {}: {}", failed, describe_error(&err))),
+                }
+                return Some(Message::RefreshTorrents);
+            },
+            ApiResponse::TorrentAdded { names, skipped, result } => {
+                let added = match names.as_slice() {
+                    [name] => name.clone(),
+                    _ => format!("{} torrents", names.len()),
+                };
+                match result {
+                    Ok(_) if skipped.is_empty() => {
+                        self.notify(NotificationLevel::Success, format!("Added {}", added));
+                        return Some(Message::CloseAddTorrent);
+                    },
+                    Ok(_) => {
+                        self.notify(NotificationLevel::Warning, 
+                            format!("Added {}, {} failed: {}", added, skipped.len(), skipped.join(", ")));
+                        return Some(Message::CloseAddTorrent);
+                    },
+                    Err(err) => {
+                        let mut failed = format!("Failed to add {}: {}", added, describe_error(&err));
+                        if !skipped.is_empty() {
+                            failed.push_str(&format!(", also failed: {}", skipped.join(", ")));
+                        }
+                        self.notify(NotificationLevel::Error, failed);
+                        return Some(Message::RefreshTorrents);
+                    },
+                }
             },
-            Err(_err) => {}
         }
-        Ok(Message::RefreshTorrents)
+        None
     }
-}
\ No newline at end of file
+}
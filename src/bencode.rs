@@ -0,0 +1,242 @@
+use std::{collections::BTreeMap, fmt, ops::Range};
+
+/// Nesting limit so a malicious file can't overflow the stack.
+const MAX_DEPTH: usize = 64;
+
+/// A decoded bencode value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Integer(i64),
+    Bytes(Vec<u8>),
+    List(Vec<Value>),
+    Dict(BTreeMap<Vec<u8>, Value>),
+}
+
+impl Value {
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            Value::Integer(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Value::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// Returns the bytes as a string, replacing invalid UTF-8.
+    pub fn as_string(&self) -> Option<String> {
+        self.as_bytes().map(|bytes| String::from_utf8_lossy(bytes).to_string())
+    }
+
+    pub fn as_list(&self) -> Option<&[Value]> {
+        match self {
+            Value::List(list) => Some(list),
+            _ => None,
+        }
+    }
+
+    pub fn as_dict(&self) -> Option<&BTreeMap<Vec<u8>, Value>> {
+        match self {
+            Value::Dict(dict) => Some(dict),
+            _ => None,
+        }
+    }
+
+    /// Look up a key if this is a dictionary.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.as_dict().and_then(|dict| dict.get(key.as_bytes()))
+    }
+}
+
+/// Error for data that isn't valid bencode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodeError {
+    pub position: usize,
+    pub message: &'static str,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// A decoded document with the position of its top level info dictionary.
+#[derive(Debug)]
+pub struct Decoded {
+    pub value: Value,
+    /// Byte range of the value under the top level "info" key, hashed for the info-hash.
+    pub info: Option<Range<usize>>,
+}
+
+/// Decode a bencode document, which must be a single value with nothing after it.
+pub fn decode(data: &[u8]) -> Result<Decoded, DecodeError> {
+    let mut decoder = Decoder { data, pos: 0, info: None };
+    let value = decoder.value(0)?;
+    if decoder.pos != data.len() {
+        return Err(decoder.error("unexpected data after the end"));
+    }
+    Ok(Decoded { value, info: decoder.info })
+}
+
+struct Decoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+    info: Option<Range<usize>>,
+}
+
+impl Decoder<'_> {
+    fn error(&self, message: &'static str) -> DecodeError {
+        DecodeError { position: self.pos, message }
+    }
+
+    fn peek(&self) -> Result<u8, DecodeError> {
+        self.data.get(self.pos).copied().ok_or_else(|| self.error("unexpected end of data"))
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value, DecodeError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("nested too deeply"));
+        }
+        match self.peek()? {
+            b'i' => {
+                self.pos += 1;
+                let value = self.integer(b'e')?;
+                Ok(Value::Integer(value))
+            },
+            b'0'..=b'9' => self.bytes().map(|bytes| Value::Bytes(bytes.to_vec())),
+            b'l' => {
+                self.pos += 1;
+                let mut list = vec![];
+                while self.peek()? != b'e' {
+                    list.push(self.value(depth + 1)?);
+                }
+                self.pos += 1;
+                Ok(Value::List(list))
+            },
+            b'd' => {
+                self.pos += 1;
+                let mut dict = BTreeMap::new();
+                while self.peek()? != b'e' {
+                    if !self.peek()?.is_ascii_digit() {
+                        return Err(self.error("dictionary key is not a string"));
+                    }
+                    let key = self.bytes()?.to_vec();
+                    let start = self.pos;
+                    let value = self.value(depth + 1)?;
+                    if depth == 0 && key == b"info" {
+                        self.info = Some(start..self.pos);
+                    }
+                    dict.insert(key, value);
+                }
+                self.pos += 1;
+                Ok(Value::Dict(dict))
+            },
+            _ => Err(self.error("unexpected character")),
+        }
+    }
+
+    /// Read an integer up to the terminator, rejecting leading zeros and negative zero.
+    fn integer(&mut self, terminator: u8) -> Result<i64, DecodeError> {
+        let start = self.pos;
+        let end = self.data[start..]
+            .iter()
+            .position(|b| *b == terminator)
+            .map(|i| start + i)
+            .ok_or_else(|| self.error("unterminated integer"))?;
+        let text = std::str::from_utf8(&self.data[start..end]).map_err(|_| self.error("invalid integer"))?;
+        let digits = text.strip_prefix('-').unwrap_or(text);
+        if digits.is_empty()
+            || !digits.bytes().all(|b| b.is_ascii_digit())
+            || (digits.len() > 1 && digits.starts_with('0'))
+            || text == "-0" {
+            return Err(self.error("invalid integer"));
+        }
+        let value = text.parse().map_err(|_| self.error("integer out of range"))?;
+        self.pos = end + 1;
+        Ok(value)
+    }
+
+    fn bytes(&mut self) -> Result<&[u8], DecodeError> {
+        let length = self.integer(b':')?;
+        let start = self.pos;
+        let end = usize::try_from(length)
+            .ok()
+            .and_then(|length| start.checked_add(length))
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| self.error("string longer than the data"))?;
+        self.pos = end;
+        Ok(&self.data[start..end])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes(value: &str) -> Value {
+        Value::Bytes(value.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn values() {
+        assert_eq!(decode(b"i42e").unwrap().value, Value::Integer(42));
+        assert_eq!(decode(b"i-7e").unwrap().value, Value::Integer(-7));
+        assert_eq!(decode(b"i0e").unwrap().value, Value::Integer(0));
+        assert_eq!(decode(b"4:spam").unwrap().value, bytes("spam"));
+        assert_eq!(decode(b"0:").unwrap().value, bytes(""));
+        assert_eq!(decode(b"l4:spami1ee").unwrap().value, Value::List(vec![bytes("spam"), Value::Integer(1)]));
+
+        let dict = decode(b"d3:bar4:spam3:fooi42ee").unwrap().value;
+        assert_eq!(dict.get("bar"), Some(&bytes("spam")));
+        assert_eq!(dict.get("foo").and_then(Value::as_integer), Some(42));
+        assert_eq!(dict.get("baz"), None);
+    }
+
+    #[test]
+    fn info_range() {
+        let data = b"d8:announce3:url4:infod4:name1:xee";
+        let decoded = decode(data).unwrap();
+        assert_eq!(&data[decoded.info.unwrap()], b"d4:name1:xe");
+        // Only the top level info key counts.
+        assert_eq!(decode(b"d1:ad4:infoi1eee").unwrap().info, None);
+    }
+
+    #[test]
+    fn invalid() {
+        for data in [
+            &b""[..],
+            b"i42",
+            b"ie",
+            b"i-e",
+            b"i-0e",
+            b"i03e",
+            b"i1.5e",
+            b"i99999999999999999999e",
+            b"5:spam",
+            b"-1:a",
+            b"l4:spam",
+            b"di1e1:ae",
+            b"d1:ae",
+            b"x",
+            b"i1ei2e",
+        ] {
+            assert!(decode(data).is_err(), "{:?} should be rejected", String::from_utf8_lossy(data));
+        }
+    }
+
+    #[test]
+    fn depth_limit() {
+        let nested = |depth: usize| [vec![b'l'; depth], vec![b'e'; depth]].concat();
+        assert!(decode(&nested(MAX_DEPTH + 1)).is_ok());
+        let err = decode(&nested(MAX_DEPTH + 2)).unwrap_err();
+        assert_eq!(err.message, "nested too deeply");
+        assert_eq!(err.to_string(), format!("nested too deeply at byte {}", MAX_DEPTH + 1));
+    }
+}
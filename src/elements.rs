@@ -1,19 +1,23 @@
-use crate::{enums::{SelectedAddTorrentTab, SelectedInfoTab}, App, CurentInput};
+use crate::{magnet::check_link, metainfo::TorrentPreview, enums::{AddOptionField, CategoryField, CategoryForm, ConnectionState, NotificationLevel, SelectedAddTorrentTab, SelectedInfoTab, SidebarEntry, SpeedLimitField}, App, CurentInput, InputMode};
 use ratatui::{
     layout::{Constraint, Alignment, Position, Layout, Rect},
     style::{Color, Modifier, Style, Stylize},
     text::{Line, Text},
-    widgets::{Block, BorderType, Borders, Cell, Gauge, Paragraph, 
-        Row, Scrollbar, ScrollbarOrientation, Table, Tabs},
+    widgets::{Block, BorderType, Borders, Cell, Clear, Gauge, List, ListItem, Paragraph, 
+        Row, Scrollbar, ScrollbarOrientation, Table, Tabs, Wrap},
     Frame
 };
-use qbit_rs::model::TrackerStatus;
+use qbit_rs::model::{Priority, TorrentContent, TrackerStatus};
 
 const TABLE_ITEM_HEIGHT: usize = 2;
-const INFO_TAB_DETAILS: usize = 11;
-const INFO_TEXT: [&str; 2] = [
+const TOAST_WIDTH: u16 = 50;
+const MAX_TOASTS: usize = 4;
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+pub const INFO_TAB_DETAILS: usize = 11;
+const INFO_TEXT: [&str; 3] = [
     "(Esc) quit | (Tab) details | (↑) move up | (↓) move down | (←) move left | (→) move right",
-    "(Ctrl + e) edit cfg | (r) refresh | (k) move up | (j) move down | (h) move left | (l) move right",
+    "(Ctrl + e) edit cfg | (r) refresh | (k) move up | (j) move down | (h) move left | (l) move right | (PgUp/PgDn/Home/End) page",
+    "(p) pause | (s) resume | (f) force start | (c) recheck | (a) reannounce | (d) delete | (Space) select | (v) range | (A) all | (o/O) sort | (/) search | (n/N) next/prev result (wraps) | (b) filters | (Shift + Tab) focus filters | (m) messages | (t) alt speed | (Ctrl + l) speed limits | (Ctrl + t) torrent limits | (R) rename | (M) move | (g) set category | (G) categories | (C) category column",
 ];
 
 impl App {
@@ -28,6 +32,87 @@ impl App {
         frame.render_widget(info, area);
     }
 
+    /// Renders the status bar with the state of the connection to the server.
+    pub fn render_status_bar(&self, frame: &mut Frame, area: Rect) {
+        let (status, color) = match &self.connection {
+            ConnectionState::Connecting => ("Connecting...".to_string(), Color::Yellow),
+            ConnectionState::Connected { version, api_version } => {
+                (format!("Connected to qBittorrent {} (WebAPI v{})", version, api_version), Color::Green)
+            },
+            ConnectionState::AuthFailed => {
+                ("Login failed, check the config (Ctrl + e) or press (r) to retry".to_string(), Color::LightRed)
+            },
+            ConnectionState::Banned => {
+                ("IP banned by the server for too many failed logins, press (r) to retry once the ban ends".to_string(), Color::LightRed)
+            },
+            ConnectionState::Unreachable => {
+                let retry = match self.next_refresh_in() {
+                    Some(wait) => format!(", retrying in {}s", wait.as_secs_f64().ceil() as u64),
+                    None => ", press (r) to retry".to_string(),
+                };
+                (format!("Server unreachable{}", retry), Color::LightRed)
+            },
+        };
+        let mut spans = vec![" ● ".fg(color), status.fg(Color::White)];
+        if !self.connection.is_connected() && !self.torrents.is_empty() {
+            spans.push(" | showing the last torrent list".fg(Color::DarkGray));
+        }
+        let transfer = self.transfer_status();
+        let horizontal = Layout::horizontal([Constraint::Fill(1), Constraint::Length(transfer.width() as u16)]);
+        let rects = horizontal.split(area);
+        let status_bar = Paragraph::new(Line::from(spans))
+            .style(Style::new().bg(Color::Black));
+        frame.render_widget(status_bar, rects[0]);
+        let transfer = Paragraph::new(transfer)
+            .style(Style::new().bg(Color::Black))
+            .alignment(Alignment::Right);
+        frame.render_widget(transfer, rects[1]);
+    }
+
+    /// Global transfer rates, session totals, free space and DHT nodes from the server state.
+    fn transfer_status(&self) -> Line<'static> {
+        let state = self.torrent_sync.server_state();
+        if !self.connection.is_connected() || state.dl_info_speed.is_none() {
+            return Line::default();
+        }
+        let limit = |limit: Option<i64>| match limit {
+            Some(limit) if limit > 0 => format!(" [{}]", self.format_rate(limit)),
+            _ => String::new(),
+        };
+        let mut spans = vec![
+            format!("↓ {}{} ({})", 
+                self.format_rate(state.dl_info_speed.unwrap_or(0)),
+                limit(state.dl_rate_limit),
+                self.format_bytes(state.dl_info_data.unwrap_or(0))).fg(Color::LightGreen),
+            "  ".into(),
+            format!("↑ {}{} ({})",
+                self.format_rate(state.up_info_speed.unwrap_or(0)),
+                limit(state.up_rate_limit),
+                self.format_bytes(state.up_info_data.unwrap_or(0))).fg(Color::LightBlue),
+        ];
+        if state.use_alt_speed_limits.unwrap_or(false) {
+            spans.push(" | ".into());
+            spans.push("Alt speed".fg(Color::Yellow));
+        }
+        if let Some(free_space) = state.free_space_on_disk {
+            spans.push(format!(" | Free: {}", self.format_bytes(free_space)).into());
+        }
+        if let Some(dht_nodes) = state.dht_nodes {
+            spans.push(format!(" | DHT: {}", dht_nodes).into());
+        }
+        if let Some(status) = &state.connection_status {
+            let color = match status.as_str() {
+                "connected" => Color::Green,
+                "firewalled" => Color::Yellow,
+                _ => Color::LightRed,
+            };
+            spans.push(" | ".into());
+            spans.push(status.clone().fg(color));
+        }
+        spans.push(" ".into());
+        Line::from(spans).fg(Color::White)
+    }
+
     /// Renders the config popup.
     /// Takes user input for api_url, username and password.
     pub fn render_cfg_popup(&self, frame: &mut Frame, area: Rect) {
@@ -48,7 +133,7 @@ impl App {
             .alignment(Alignment::Left);
         frame.render_widget(cfg_paragraph, rects[0]);
         let cfg_save_text = vec![
-            Line::from("Press (Ctrl + e) to close this popup (without saving)."),
+            Line::from("Press (Esc/Ctrl + e) to close this popup (without saving)."),
             Line::from("Press (Ctrl + s) to save the config."),
         ];
         let help_text = Paragraph::new(cfg_save_text)
@@ -72,9 +157,8 @@ impl App {
     /// Renders the add torrent popup.
     /// Displays tabs for magnet link and torrent file.
     pub fn render_add_torrent_popup(&self, frame: &mut Frame, area: Rect) {
-        // TODO: Add support for torrent files.
         let veritcal = Layout::vertical(
-            [Constraint::Length(3), Constraint::Fill(1)]
+            [Constraint::Length(3), Constraint::Fill(1), Constraint::Length(AddOptionField::ALL.len() as u16 + 3)]
         );
         let rects = veritcal.split(area);
         self.render_add_tabs(frame, rects[0]);
@@ -82,7 +166,108 @@ impl App {
             SelectedAddTorrentTab::MagnetLink => self.render_add_magent(frame, rects[1]),
             SelectedAddTorrentTab::File => self.render_add_file(frame, rects[1]),
         }
-        // TODO: Add block to support for user choice on the other options in AddTorrentArg
+        self.render_add_options(frame, rects[2]);
+    }
+
+    /// Renders the metadata and file tree of a torrent file before it is added.
+    fn render_torrent_preview(&self, frame: &mut Frame, area: Rect, preview: &TorrentPreview) {
+        let metainfo = &preview.metainfo;
+        let vertical = Layout::vertical(
+            [Constraint::Length(8), Constraint::Fill(1)]
+        );
+        let rects = vertical.split(area);
+        let block = Block::bordered().style(Style::new().fg(Color::White).bg(Color::Black));
+        let trackers = match metainfo.trackers.as_slice() {
+            [] => "None".to_string(),
+            [tracker] => tracker.clone(),
+            [tracker, rest @ ..] => format!("{} (+{} more)", tracker, rest.len()),
+        };
+        let info_text = vec![
+            Line::from(format!("Name:       {}", metainfo.name)),
+            Line::from(format!("Total size: {} in {} files", self.format_bytes(metainfo.total_size), metainfo.files.len())),
+            Line::from(format!("Piece size: {}", self.format_bytes(metainfo.piece_length))),
+            Line::from(format!("Private:    {}", if metainfo.private { "Yes" } else { "No" })),
+            Line::from(format!("Info hash:  {}", metainfo.info_hash)),
+            Line::from(format!("Trackers:   {}", trackers)),
+        ];
+        let info = Paragraph::new(info_text)
+            .style(Style::new().fg(Color::White).bg(Color::Black))
+            .block(block.clone().title(" Torrent Preview ").title_alignment(Alignment::Center));
+        frame.render_widget(info, rects[0]);
+
+        let items: Vec<ListItem> = preview.rows.iter()
+            .map(|row| {
+                let checkbox = match preview.row_wanted(row) {
+                    Some(true) => "[x]",
+                    Some(false) => "[ ]",
+                    None => "[-]",
+                };
+                let indent = "  ".repeat(row.depth);
+                let line = match row.file {
+                    Some(i) => format!("{} {}{} ({})", checkbox, indent, row.label,
+                        self.format_bytes(metainfo.files[i].length)),
+                    None => format!("{} {}{}/", checkbox, indent, row.label),
+                };
+                ListItem::new(line)
+            })
+            .collect();
+        let wanted = preview.wanted.iter().filter(|wanted| **wanted).count();
+        let files = List::new(items)
+            .style(Style::new().fg(Color::White).bg(Color::Black))
+            .highlight_style(Style::new().bold().fg(Color::Black).bg(Color::LightBlue))
+            .block(block.clone().title(format!(" Files ({} of {} selected) ", wanted, metainfo.files.len())));
+        let mut state = preview.state.clone();
+        frame.render_stateful_widget(files, rects[1], &mut state);
+    }
+
+    /// Renders the options form for adding a torrent.
+    /// The highlighted field is only shown while the form has focus.
+    fn render_add_options(&self, frame: &mut Frame, area: Rect) {
+        const LABEL_WIDTH: usize = 24;
+        let options = &self.add_options;
+        let checkbox = |value: bool| if value { "[x]" } else { "[ ]" }.to_string();
+        let mut lines = vec![];
+        for field in AddOptionField::ALL {
+            let value = match field {
+                AddOptionField::SavePath => options.savepath.clone(),
+                AddOptionField::Category if options.category.is_empty() => "◂ None ▸".to_string(),
+                AddOptionField::Category => format!("◂ {} ▸", options.category),
+                AddOptionField::Tags => options.tags.clone(),
+                AddOptionField::Rename => options.rename.clone(),
+                AddOptionField::DownloadLimit => options.download_limit.clone(),
+                AddOptionField::UploadLimit => options.upload_limit.clone(),
+                AddOptionField::Paused => checkbox(options.paused),
+                AddOptionField::SkipChecking => checkbox(options.skip_checking),
+                AddOptionField::ContentLayout => format!("◂ {} ▸", options.content_layout.label()),
+                AddOptionField::SequentialDownload => checkbox(options.sequential_download),
+                AddOptionField::FirstLastPiecePriority => checkbox(options.first_last_piece_priority),
+            };
+            let line = Line::from(format!("{:<width$}{}", format!("{}:", field.label()), value, width = LABEL_WIDTH));
+            if self.add_options_focus && field == self.add_option_field {
+                lines.push(line.fg(Color::LightRed));
+            } else {
+                lines.push(line);
+            }
+        }
+        lines.push(Line::from(
+            "(Ctrl + o) focus options | (↑/↓) field | (Space/←/→) change | (Ctrl + s) save as defaults"
+        ).fg(Color::DarkGray));
+        let border = if self.add_options_focus { Color::LightRed } else { Color::White };
+        let options_paragraph = Paragraph::new(lines)
+            .style(Style::new().fg(Color::White).bg(Color::Black))
+            .block(Block::bordered()
+                .border_style(Style::new().fg(border))
+                .title(" Options ")
+                .title_alignment(Alignment::Center));
+        frame.render_widget(Clear, area);
+        frame.render_widget(options_paragraph, area);
+
+        // Render the input cursor for text fields.
+        if self.add_options_focus && self.add_option_field.is_text() {
+            let x = area.x + LABEL_WIDTH as u16 + self.charcter_index as u16 + 1;
+            let y = area.y + self.add_option_field.to_index() as u16 + 1;
+            frame.set_cursor_position(Position::new(x, y));
+        }
     }
 
     /// Renders the tabs for the add torrent popup.
@@ -102,8 +287,9 @@ impl App {
 
     /// Renders the magnet link input field for adding a torrent.
     fn render_add_magent(&self, frame: &mut Frame, area: Rect) {
+        let links: Vec<&str> = self.magnet_link.split_whitespace().collect();
         let vertical = Layout::vertical(
-            [Constraint::Length(3), Constraint::Length(4)]
+            [Constraint::Length(3), Constraint::Length(4), Constraint::Fill(1)]
         );
         let rects = vertical.split(area);
         // Ensure the scroll offset does not exceed the length of the magnet link.
@@ -114,7 +300,8 @@ impl App {
         }
 
         let block = Block::bordered().style(Style::new().fg(Color::White).bg(Color::Black));
-        let magnet_text = Line::from(format!("Magnet Link: {}", self.magnet_link.as_str()));
+        // Pasted links are separated by newlines, shown as spaces so the cursor stays on one line.
+        let magnet_text = Line::from(format!("Magnet Link: {}", self.magnet_link.replace('\n', " ")));
         let magnet_paragraph = Paragraph::new(magnet_text)
             .style(Style::new().fg(Color::White).bg(Color::Black))
             .block(block.clone().title(" Add Torrent ").title_alignment(Alignment::Center))
@@ -122,14 +309,17 @@ impl App {
             .scroll((0, magent_scroll_offset));
         frame.render_widget(magnet_paragraph, rects[0]);
         let add_text = vec![
-            Line::from("(Tab) to toggle tab | (Ctrl + a) to close this popup (without adding torrent)."),
-            Line::from("(Enter) to add the torrent | Press (Ctrl + w) to clear the magnet link."),
+            Line::from("(Tab) to toggle tab | (Esc/Ctrl + a) to close this popup (without adding torrent)."),
+            Line::from("(Enter) to add the torrents | Paste several links to add them together | (Ctrl + w) clear."),
         ];
         let help_text = Paragraph::new(add_text)
             .style(Style::new().fg(Color::White).bg(Color::Black))
             .block(block.clone())
             .alignment(Alignment::Center);
         frame.render_widget(help_text, rects[1]);
+        if !links.is_empty() {
+            self.render_magnet_preview(frame, rects[2], &links);
+        }
 
         // Render the input cursor.
         // Constrain cursor within the scrolled window.
@@ -140,36 +330,573 @@ impl App {
         // Clamp to right edge of the box.
         let x = x.min(rects[0].x + rects[0].width.saturating_sub(2));
         let y = rects[0].y + 1;
-        frame.set_cursor_position(Position::new(x, y));
+        if !self.add_options_focus {
+            frame.set_cursor_position(Position::new(x, y));
+        }
     }
 
-    /// Renders a file input field for specifying a torrent file.
-    /// Currently a placeholder as the functionality is not implemented.
+    /// Renders what was parsed from each link as it is typed, flagging malformed links 
+    /// and torrents that are already in the torrent list.
+    fn render_magnet_preview(&self, frame: &mut Frame, area: Rect, links: &[&str]) {
+        let mut lines = vec![];
+        for link in links {
+            match check_link(link) {
+                Ok(Some(magnet)) => {
+                    let id = magnet.torrent_id().unwrap_or_default();
+                    lines.push(Line::from(format!("✓ {}", magnet.name.clone().unwrap_or_else(|| id.clone())))
+                        .fg(Color::Green));
+                    if let Some(hash) = &magnet.info_hash {
+                        lines.push(Line::from(format!("    Info hash v1: {}", hash)));
+                    }
+                    if let Some(hash) = &magnet.info_hash_v2 {
+                        lines.push(Line::from(format!("    Info hash v2: {}", hash)));
+                    }
+                    let size = magnet.length.map_or("Unknown".to_string(), |length| self.format_bytes(length as i64));
+                    lines.push(Line::from(format!("    Size: {} | Trackers: {}", size, magnet.trackers.len())));
+                    if self.torrent_sync.contains(&id) {
+                        lines.push(Line::from("    ⚠ Already in the torrent list").fg(Color::Yellow));
+                    }
+                },
+                Ok(None) => lines.push(Line::from(format!("✓ URL: {}", link)).fg(Color::Green)),
+                Err(err) => lines.push(Line::from(format!("✗ {}: {}", link, err)).fg(Color::LightRed)),
+            }
+        }
+        let title = match links.len() {
+            1 => " Preview ".to_string(),
+            count => format!(" Preview ({} links) ", count),
+        };
+        let preview = Paragraph::new(lines)
+            .style(Style::new().fg(Color::White).bg(Color::Black))
+            .block(Block::bordered()
+                .style(Style::new().fg(Color::White).bg(Color::Black))
+                .title(title));
+        frame.render_widget(preview, area);
+    }
+
+    /// Renders a file explorer for picking torrent files, with the files marked to add together.
     fn render_add_file(&self, frame: &mut Frame, area: Rect) {
+        // Show the marked files under the explorer, up to a few at a time.
+        let marked_height = match self.marked_torrent_files.len() {
+            0 => 0,
+            count => count.min(5) as u16 + 2,
+        };
         let vertical = Layout::vertical(
-            [Constraint::Fill(1), Constraint::Length(4)]
+            [Constraint::Fill(1), Constraint::Length(marked_height), Constraint::Length(4)]
         );
         let rects = vertical.split(area);
+        if let Some(preview) = self.torrent_preview.as_ref() {
+            self.render_torrent_preview(frame, rects[0], preview);
+            let help_text = Paragraph::new(vec![
+                Line::from("(Enter) add the torrent | (Space) tick/untick file or folder | (Backspace) back to files"),
+                Line::from("(↑/k) move up | (↓/j) move down | Unticked files are not downloaded"),
+            ])
+                .style(Style::new().fg(Color::White).bg(Color::Black))
+                .block(Block::bordered().style(Style::new().fg(Color::White).bg(Color::Black)))
+                .alignment(Alignment::Center);
+            frame.render_widget(help_text, rects[2]);
+            return;
+        }
         frame.render_widget(&self.file_explorer.as_ref().unwrap().widget(), rects[0]);
+        if !self.marked_torrent_files.is_empty() {
+            let items: Vec<ListItem> = self.marked_torrent_files.iter()
+                .map(|path| ListItem::new(format!("● {}", path.to_string_lossy())))
+                .collect();
+            let marked = List::new(items)
+                .style(Style::new().fg(Color::LightYellow).bg(Color::Black))
+                .block(Block::bordered()
+                    .style(Style::new().fg(Color::White).bg(Color::Black))
+                    .title(format!(" {} marked ", self.marked_torrent_files.len())));
+            frame.render_widget(marked, rects[1]);
+        }
 
         let add_text = vec![
             Line::from("(Tab) to toggle tab | (↑) move up | (↓) move down | (←) move up dir | (→) move down dir"),
-            Line::from("(Enter) select torrent file | (k) move up | (j) move down | (h) move up dir | (l) move down dir")
+            Line::from("(Enter) add marked or selected file | (Space) mark file | (k/j) up/down | (h/l) up/down dir")
         ];
         let help_text = Paragraph::new(add_text)
             .style(Style::new().fg(Color::White).bg(Color::Black))
             .block(Block::bordered().style(Style::new().fg(Color::White).bg(Color::Black)))
             .alignment(Alignment::Center);
+        frame.render_widget(help_text, rects[2]);
+    }
+
+    /// Renders the filter sidebar with the number of torrents for each entry.
+    /// Active filters are highlighted and the cursor is only shown while the sidebar has focus.
+    pub fn render_sidebar(&mut self, frame: &mut Frame, area: Rect) {
+        let mut items = vec![];
+        for (entry, count) in self.sidebar_entries.iter() {
+            let label = match entry {
+                SidebarEntry::Header(title) => {
+                    items.push(ListItem::new(Line::from(*title).bold().fg(Color::LightRed)));
+                    continue;
+                },
+                SidebarEntry::Status(status) => status.label().to_string(),
+                SidebarEntry::Category(None) | SidebarEntry::Tag(None) | SidebarEntry::Tracker(None) => "All".to_string(),
+                SidebarEntry::Category(Some(c)) if c.is_empty() => "Uncategorized".to_string(),
+                SidebarEntry::Tag(Some(t)) if t.is_empty() => "Untagged".to_string(),
+                SidebarEntry::Tracker(Some(t)) if t.is_empty() => "Trackerless".to_string(),
+                SidebarEntry::Category(Some(name)) | SidebarEntry::Tag(Some(name)) | SidebarEntry::Tracker(Some(name)) => name.clone(),
+            };
+            let style = if self.sidebar_entry_active(entry) {
+                Style::default().fg(Color::LightBlue).bold()
+            } else {
+                Style::default().fg(Color::White)
+            };
+            items.push(ListItem::new(Line::from(format!("  {} ({})", label, count))).style(style));
+        }
+        let focused = self.input_mode == InputMode::Sidebar;
+        let border_color = if focused { Color::LightBlue } else { Color::White };
+        let list = List::new(items)
+            .block(Block::bordered()
+                .title(" Filters ")
+                .border_style(Style::new().fg(border_color)))
+            .style(Style::new().fg(Color::White).bg(Color::Black))
+            .highlight_style(Style::default().bg(Color::LightBlue).fg(Color::Black));
+        if focused {
+            frame.render_stateful_widget(list, area, &mut self.sidebar_state);
+        } else {
+            frame.render_widget(list, area);
+        }
+    }
+
+    /// Renders the torrent name search bar with the number of matching torrents.
+    pub fn render_search_bar(&self, frame: &mut Frame, area: Rect) {
+        let mode = if self.search_regex { "regex" } else { "text" };
+        let invalid = self.search_pattern.is_none() && !self.search_query.is_empty();
+        let status = if invalid {
+            "invalid regex".to_string()
+        } else {
+            format!("{} of {} torrents", self.torrents.len(), self.torrent_sync.len())
+        };
+        let block = Block::bordered()
+            .style(Style::new().fg(Color::White).bg(Color::Black))
+            .title(format!(" Search ({}) ", mode))
+            .title_bottom(Line::from(" (Enter/Esc) close | (Ctrl + r) regex | (n/N) cycle through the results "))
+            .title_bottom(Line::from(format!(" {} ", status)).right_aligned());
+        let block = if invalid { block.border_style(Style::new().fg(Color::LightRed)) } else { block };
+        let search = Paragraph::new(Line::from(format!("/{}", self.search_query)))
+            .style(Style::new().fg(Color::White).bg(Color::Black))
+            .block(block);
+        frame.render_widget(search, area);
+
+        // Render the input cursor while typing.
+        if self.input_mode == InputMode::Search {
+            let x = area.x + 2 + self.charcter_index as u16;
+            let x = x.min(area.x + area.width.saturating_sub(2));
+            frame.set_cursor_position(Position::new(x, area.y + 1));
+        }
+    }
+
+    /// Renders the active notifications as toasts stacked in the top right corner.
+    pub fn render_toasts(&self, frame: &mut Frame, area: Rect) {
+        let width = TOAST_WIDTH.min(area.width);
+        let mut y = area.y + 1;
+        for notification in self.notifications.active().take(MAX_TOASTS) {
+            // Wrap the message inside the borders of the toast.
+            let lines = notification.message.chars().count().div_ceil(width.saturating_sub(2).max(1) as usize);
+            let height = (lines as u16 + 2).min(area.height);
+            if y + height > area.y + area.height {
+                break;
+            }
+            let toast_area = Rect::new(area.x + area.width - width, y, width, height);
+            let color = self.notification_color(notification.level);
+            let toast = Paragraph::new(notification.message.as_str())
+                .wrap(Wrap { trim: true })
+                .style(Style::new().fg(Color::White).bg(Color::Black))
+                .block(Block::bordered()
+                    .border_style(Style::new().fg(color))
+                    .title(self.notification_title(notification.level)));
+            frame.render_widget(Clear, toast_area);
+            frame.render_widget(toast, toast_area);
+            y += height;
+        }
+    }
+
+    /// Renders the history of notifications, newest first.
+    pub fn render_notifications_popup(&self, frame: &mut Frame, area: Rect) {
+        let vertical = Layout::vertical(
+            [Constraint::Fill(1), Constraint::Length(3)]
+        );
+        let rects = vertical.split(area);
+        frame.render_widget(Clear, area);
+        let lines: Vec<Line> = self.notifications.history()
+            .map(|notification| {
+                let color = self.notification_color(notification.level);
+                Line::from(vec![
+                    format!("{} ", notification.timestamp).fg(Color::DarkGray),
+                    format!("{:<8}", self.notification_title(notification.level)).fg(color),
+                    notification.message.clone().into(),
+                ])
+            })
+            .collect();
+        let history = Paragraph::new(lines)
+            .style(Style::new().fg(Color::White).bg(Color::Black))
+            .block(Block::bordered()
+                .title(format!(" Notifications ({}) ", self.notifications.len()))
+                .title_alignment(Alignment::Center))
+            .scroll((self.notifications_scroll, 0));
+        frame.render_widget(history, rects[0]);
+        let help_text = Paragraph::new(Line::from("(Esc/m) close | (↑/k) scroll up | (↓/j) scroll down"))
+            .style(Style::new().fg(Color::White).bg(Color::Black))
+            .block(Block::bordered())
+            .alignment(Alignment::Center);
         frame.render_widget(help_text, rects[1]);
     }
 
+    fn notification_color(&self, level: NotificationLevel) -> Color {
+        match level {
+            NotificationLevel::Info => Color::LightBlue,
+            NotificationLevel::Success => Color::Green,
+            NotificationLevel::Warning => Color::Yellow,
+            NotificationLevel::Error => Color::LightRed,
+        }
+    }
+
+    fn notification_title(&self, level: NotificationLevel) -> &'static str {
+        match level {
+            NotificationLevel::Info => "Info",
+            NotificationLevel::Success => "Success",
+            NotificationLevel::Warning => "Warning",
+            NotificationLevel::Error => "Error",
+        }
+    }
+
+    /// Renders the global speed limits popup.
+    /// Takes user input for the download and upload limits.
+    pub fn render_speed_limits_popup(&self, frame: &mut Frame, area: Rect) {
+        let vertical = Layout::vertical(
+            [Constraint::Length(6), Constraint::Length(4)]
+        );
+        let rects = vertical.split(area);
+        let block = Block::bordered().style(Style::new().fg(Color::White).bg(Color::Black));
+        frame.render_widget(Clear, area);
+        let alt_speed = self.torrent_sync.server_state().use_alt_speed_limits.unwrap_or(false);
+        let limits_text = vec![
+            Line::from(format!("Download: {}", self.download_limit_input)),
+            Line::from(format!("Upload:   {}", self.upload_limit_input)),
+            Line::from(""),
+            Line::from(if alt_speed { "Alternative speed limits are on" } else { "Alternative speed limits are off" })
+                .fg(Color::DarkGray),
+        ];
+        let limits_paragraph = Paragraph::new(limits_text)
+            .style(Style::new().fg(Color::White).bg(Color::Black))
+            .block(block.clone().title(" Global speed limits ").title_alignment(Alignment::Center))
+            .alignment(Alignment::Left);
+        frame.render_widget(limits_paragraph, rects[0]);
+        let help_text = vec![
+            Line::from("Rates like 5M or 500K, empty or 0 for unlimited."),
+            Line::from("(Enter) save | (Tab) next field | (Esc/Ctrl + l) close"),
+        ];
+        let help_paragraph = Paragraph::new(help_text)
+            .style(Style::new().fg(Color::White).bg(Color::Black))
+            .block(block.clone())
+            .alignment(Alignment::Left);
+        frame.render_widget(help_paragraph, rects[1]);
+
+        // Render the input cursor
+        let label = "Download: ";
+        let line_index = match self.speed_limit_field {
+            SpeedLimitField::Download => 1,
+            SpeedLimitField::Upload => 2,
+        };
+        let x = rects[0].x + label.len() as u16 + self.charcter_index as u16 + 1;
+        let y = rects[0].y + line_index;
+        frame.set_cursor_position(Position::new(x, y));
+    }
+
+    /// Renders the move popup with the new save path and the file explorer to browse local mounts.
+    /// Browsing sets the path to the opened directory.
+    pub fn render_move_popup(&self, frame: &mut Frame, area: Rect) {
+        let vertical = Layout::vertical(
+            [Constraint::Length(3), Constraint::Fill(1), Constraint::Length(4)]
+        );
+        let rects = vertical.split(area);
+        let block = Block::bordered().style(Style::new().fg(Color::White).bg(Color::Black));
+        frame.render_widget(Clear, area);
+        let count = self.selected_hashes().len();
+        let title = if count == 1 {
+            " Move torrent to ".to_string()
+        } else {
+            format!(" Move {} torrents to ", count)
+        };
+        let width = rects[0].width.saturating_sub(2) as usize;
+        let offset = self.charcter_index.saturating_sub(width.saturating_sub(1));
+        let input_style = if self.move_browse { Color::DarkGray } else { Color::White };
+        let input = Paragraph::new(self.move_input.as_str())
+            .style(Style::new().fg(input_style).bg(Color::Black))
+            .scroll((0, offset as u16))
+            .block(block.clone().title(title).title_alignment(Alignment::Center));
+        frame.render_widget(input, rects[0]);
+        if let Some(explorer) = self.file_explorer.as_ref() {
+            frame.render_widget(&explorer.widget(), rects[1]);
+        }
+        let help_text = if self.move_browse {
+            vec![
+                Line::from("(↑/k) up | (↓/j) down | (→/l/Enter) open directory | (←/h/Backspace) parent"),
+                Line::from("(Tab) edit the path | (Esc/Ctrl + g) cancel"),
+            ]
+        } else {
+            vec![
+                Line::from("(Enter) move | (Ctrl + w) clear | (Tab) browse local directories"),
+                Line::from("The path is on the qBittorrent server | (Esc/Ctrl + g) cancel"),
+            ]
+        };
+        let help_paragraph = Paragraph::new(help_text)
+            .style(Style::new().fg(Color::White).bg(Color::Black))
+            .block(block.clone())
+            .alignment(Alignment::Left);
+        frame.render_widget(help_paragraph, rects[2]);
+
+        // Render the input cursor while the path is edited
+        if !self.move_browse {
+            let x = rects[0].x + 1 + (self.charcter_index - offset) as u16;
+            frame.set_cursor_position(Position::new(x, rects[0].y + 1));
+        }
+    }
+
+    /// Renders the categories manager with the save path of each category.
+    /// The form for adding or editing a category is shown under the list while it is open.
+    pub fn render_categories_popup(&mut self, frame: &mut Frame, area: Rect) {
+        let form_height = if self.category_form.is_some() { 4 } else { 0 };
+        let vertical = Layout::vertical(
+            [Constraint::Fill(1), Constraint::Length(form_height), Constraint::Length(4)]
+        );
+        let rects = vertical.split(area);
+        let block = Block::bordered().style(Style::new().fg(Color::White).bg(Color::Black));
+        frame.render_widget(Clear, area);
+        let categories = self.sorted_categories();
+        let name_width = categories.iter().map(|category| category.name.chars().count()).max().unwrap_or(0);
+        let items: Vec<ListItem> = categories.iter()
+            .map(|category| {
+                let save_path = category.save_path.to_string_lossy();
+                let save_path = if save_path.is_empty() { "(default save path)".into() } else { save_path };
+                ListItem::new(format!("{:width$}  {}", category.name, save_path, width = name_width))
+            })
+            .collect();
+        let empty = items.is_empty();
+        let list = List::new(items)
+            .style(Style::new().fg(Color::White).bg(Color::Black))
+            .highlight_style(Style::new().bold().fg(Color::Black).bg(Color::LightBlue))
+            .block(block.clone().title(" Categories ").title_alignment(Alignment::Center));
+        frame.render_stateful_widget(list, rects[0], &mut self.categories_state);
+        if empty {
+            let inner = rects[0].inner(ratatui::layout::Margin::new(1, 1));
+            frame.render_widget(Paragraph::new("No categories, press (a) to add one").fg(Color::DarkGray), inner);
+        }
+
+        if let Some(form) = self.category_form {
+            let title = match form {
+                CategoryForm::Add => " Add category ",
+                CategoryForm::Edit => " Edit category ",
+            };
+            let name_style = if form == CategoryForm::Edit { Color::DarkGray } else { Color::White };
+            let form_text = vec![
+                Line::from(format!("Name:      {}", self.category_name_input)).fg(name_style),
+                Line::from(format!("Save path: {}", self.category_path_input)),
+            ];
+            let form_paragraph = Paragraph::new(form_text)
+                .style(Style::new().fg(Color::White).bg(Color::Black))
+                .block(block.clone().title(title));
+            frame.render_widget(form_paragraph, rects[1]);
+
+            // Render the input cursor
+            let label = "Save path: ";
+            let line_index = match self.category_field {
+                CategoryField::Name => 1,
+                CategoryField::SavePath => 2,
+            };
+            let x = rects[1].x + label.len() as u16 + self.charcter_index as u16 + 1;
+            frame.set_cursor_position(Position::new(x, rects[1].y + line_index));
+        }
+
+        let help_text = match (self.category_form, &self.category_remove_pending) {
+            (Some(CategoryForm::Add), _) => vec![
+                Line::from("(Enter) add | (Tab) next field | (Ctrl + w) clear | (Esc/Ctrl + g) cancel"),
+                Line::from("An empty save path uses the default save path."),
+            ],
+            (Some(CategoryForm::Edit), _) => vec![
+                Line::from("(Enter) save | (Ctrl + w) clear | (Esc/Ctrl + g) cancel"),
+                Line::from("An empty save path uses the default save path."),
+            ],
+            (None, Some(name)) => vec![
+                Line::from(format!("Press (d) again to remove {}", name)).fg(Color::LightRed),
+                Line::from("Its torrents are left without a category."),
+            ],
+            (None, None) => vec![
+                Line::from("(a) add | (e/Enter) edit save path | (d) remove | (Esc/G) close"),
+                Line::from("(↑/k) move up | (↓/j) move down"),
+            ],
+        };
+        let help_paragraph = Paragraph::new(help_text)
+            .style(Style::new().fg(Color::White).bg(Color::Black))
+            .block(block.clone())
+            .alignment(Alignment::Left);
+        frame.render_widget(help_paragraph, rects[2]);
+    }
+
+    /// Renders the category picker for the selected torrents.
+    pub fn render_category_picker(&mut self, frame: &mut Frame, area: Rect) {
+        let vertical = Layout::vertical(
+            [Constraint::Fill(1), Constraint::Length(3)]
+        );
+        let rects = vertical.split(area);
+        let block = Block::bordered().style(Style::new().fg(Color::White).bg(Color::Black));
+        frame.render_widget(Clear, area);
+        let count = self.selected_hashes().len();
+        let title = if count == 1 {
+            " Set category ".to_string()
+        } else {
+            format!(" Set category of {} torrents ", count)
+        };
+        let mut items = vec![ListItem::new("Uncategorized").fg(Color::DarkGray)];
+        items.extend(self.sorted_categories().iter().map(|category| ListItem::new(category.name.clone())));
+        let list = List::new(items)
+            .style(Style::new().fg(Color::White).bg(Color::Black))
+            .highlight_style(Style::new().bold().fg(Color::Black).bg(Color::LightBlue))
+            .block(block.clone().title(title).title_alignment(Alignment::Center));
+        frame.render_stateful_widget(list, rects[0], &mut self.category_picker_state);
+        let help_paragraph = Paragraph::new("(Enter) set | (↑/k) up | (↓/j) down | (Esc/g) close")
+            .style(Style::new().fg(Color::White).bg(Color::Black))
+            .block(block.clone())
+            .alignment(Alignment::Left);
+        frame.render_widget(help_paragraph, rects[1]);
+    }
+
+    /// Renders the rename prompt, starting from the current name.
+    /// The input scrolls sideways so the cursor stays visible in long names.
+    pub fn render_rename_popup(&self, frame: &mut Frame, area: Rect) {
+        let vertical = Layout::vertical(
+            [Constraint::Length(3), Constraint::Length(3)]
+        );
+        let rects = vertical.split(area);
+        let block = Block::bordered().style(Style::new().fg(Color::White).bg(Color::Black));
+        frame.render_widget(Clear, area);
+        let title = match &self.rename_target {
+            Some(target) => format!(" Rename {} ", target.label()),
+            None => " Rename ".to_string(),
+        };
+        let width = rects[0].width.saturating_sub(2) as usize;
+        let offset = self.charcter_index.saturating_sub(width.saturating_sub(1));
+        let input = Paragraph::new(self.rename_input.as_str())
+            .style(Style::new().fg(Color::White).bg(Color::Black))
+            .scroll((0, offset as u16))
+            .block(block.clone().title(title).title_alignment(Alignment::Center));
+        frame.render_widget(input, rects[0]);
+        let help_paragraph = Paragraph::new("(Enter) rename | (Ctrl + w) clear | (Esc/Ctrl + r) cancel")
+            .style(Style::new().fg(Color::White).bg(Color::Black))
+            .block(block.clone())
+            .alignment(Alignment::Left);
+        frame.render_widget(help_paragraph, rects[1]);
+
+        // Render the input cursor
+        let x = rects[0].x + 1 + (self.charcter_index - offset) as u16;
+        frame.set_cursor_position(Position::new(x, rects[0].y + 1));
+    }
+
+    /// Renders the torrent limits popup.
+    /// Takes user input for the rate, ratio and seeding time limits of the selected torrents.
+    pub fn render_torrent_limits_popup(&self, frame: &mut Frame, area: Rect) {
+        let vertical = Layout::vertical(
+            [Constraint::Length(6), Constraint::Length(6)]
+        );
+        let rects = vertical.split(area);
+        let block = Block::bordered().style(Style::new().fg(Color::White).bg(Color::Black));
+        frame.render_widget(Clear, area);
+        let count = self.selected_hashes().len();
+        let title = if count == 1 {
+            " Torrent limits ".to_string()
+        } else {
+            format!(" Limits for {} torrents ", count)
+        };
+        let limits_text = vec![
+            Line::from(format!("Download limit: {}", self.torrent_dl_limit_input)),
+            Line::from(format!("Upload limit:   {}", self.torrent_up_limit_input)),
+            Line::from(format!("Ratio limit:    {}", self.ratio_limit_input)),
+            Line::from(format!("Seeding time:   {}", self.seeding_time_limit_input)),
+        ];
+        let limits_paragraph = Paragraph::new(limits_text)
+            .style(Style::new().fg(Color::White).bg(Color::Black))
+            .block(block.clone().title(title).title_alignment(Alignment::Center))
+            .alignment(Alignment::Left);
+        frame.render_widget(limits_paragraph, rects[0]);
+        let help_text = vec![
+            Line::from("Rates like 5M or 500K, empty or unlimited for only the global limit."),
+            Line::from("Ratio and seeding time (minutes, 12h or 7d) take global or unlimited."),
+            Line::from("(Enter) save | (Tab) next field | (Ctrl + w) clear field"),
+            Line::from("(Esc/Ctrl + t) close"),
+        ];
+        let help_paragraph = Paragraph::new(help_text)
+            .style(Style::new().fg(Color::White).bg(Color::Black))
+            .block(block.clone())
+            .alignment(Alignment::Left);
+        frame.render_widget(help_paragraph, rects[1]);
+
+        // Render the input cursor
+        let label = "Download limit: ";
+        let line_index = self.torrent_limit_field.to_index() as u16 + 1;
+        let x = rects[0].x + label.len() as u16 + self.charcter_index as u16 + 1;
+        let y = rects[0].y + line_index;
+        frame.set_cursor_position(Position::new(x, y));
+    }
+
+    /// Renders the delete torrent confirmation popup.
+    /// Lists the torrents to be deleted and whether their data is removed from disk.
+    pub fn render_delete_torrent_popup(&self, frame: &mut Frame, area: Rect) {
+        let vertical = Layout::vertical(
+            [Constraint::Fill(1), Constraint::Length(4)]
+        );
+        let rects = vertical.split(area);
+        let block = Block::bordered().style(Style::new().fg(Color::White).bg(Color::Black));
+        frame.render_widget(Clear, area);
+
+        let hashes = self.selected_hashes();
+        let mut delete_text = vec![Line::from("Delete the following torrents?"), Line::from("")];
+        for torrent in self.torrents.iter().filter(|t| t.hash.as_ref().is_some_and(|h| hashes.contains(h))) {
+            delete_text.push(Line::from(format!("  {}", torrent.name.clone().unwrap_or_default())));
+        }
+        delete_text.push(Line::from(""));
+        let checkbox = if self.delete_files { "[x]" } else { "[ ]" };
+        delete_text.push(Line::from(format!("{} Remove data from disk", checkbox)).fg(Color::LightRed));
+        let delete_paragraph = Paragraph::new(delete_text)
+            .style(Style::new().fg(Color::White).bg(Color::Black))
+            .block(block.clone().title(" Delete Torrent ").title_alignment(Alignment::Center))
+            .alignment(Alignment::Left);
+        frame.render_widget(delete_paragraph, rects[0]);
+
+        let help_text = vec![
+            Line::from("(y/Enter) delete | (Esc/n/d) cancel"),
+            Line::from("(Space/Tab) toggle removing data from disk"),
+        ];
+        let help_paragraph = Paragraph::new(help_text)
+            .style(Style::new().fg(Color::White).bg(Color::Black))
+            .block(block.clone())
+            .alignment(Alignment::Center);
+        frame.render_widget(help_paragraph, rects[1]);
+    }
+
     /// Renders the torrents table in the following format:
     /// | Name | Size | Bytes Downloaded | Progress | State | DL Speed | UL Speed | ETA | Ratio |
     /// | name | size | downloaded | progress | state | dlspeed | upspeed | eta | ratio |
     pub fn render_torrents_table(&mut self, frame: &mut Frame, area: Rect) {
-        let header = ["Name", "Size", "Bytes DL", "Progress", "State" ,"DL Speed", "UL Speed", "ETA", "Ratio"]
+        // Show an arrow next to the column the table is sorted by.
+        let sort_index = self.cfg.sort_column.map(|column| column.to_index());
+        let arrow = if self.cfg.sort_reverse { "▼" } else { "▲" };
+        let show_category = self.cfg.show_category_column;
+        let mut titles = vec!["Name", "Size", "Bytes DL", "Progress", "State" ,"DL Speed", "UL Speed", "ETA", "Ratio"];
+        // The optional category column comes last so the sort column indexes still match the header.
+        if show_category {
+            titles.push("Category");
+        }
+        let header = titles
             .into_iter()
-            .map(Cell::from)
+            .enumerate()
+            .map(|(i, title)| {
+                if sort_index == Some(i) {
+                    Cell::from(format!("{} {}", title, arrow))
+                } else {
+                    Cell::from(title)
+                }
+            })
             .collect::<Row>()
             .style(Style::default().bold().fg(Color::White).bg(Color::Black))
             .height(1);
@@ -187,9 +914,27 @@ impl App {
             .bg(Color::Blue)
             .fg(Color::Black);
 
+        // Only build rows for the torrents that fit in the table so large lists stay fast.
+        // Table height minus the borders and header.
+        let viewport = (area.height.saturating_sub(3) as usize / TABLE_ITEM_HEIGHT).max(1);
+        self.torrents_viewport = viewport;
+        if let Some(i) = self.state.selected() {
+            if i < self.torrents_offset {
+                self.torrents_offset = i;
+            } else if i >= self.torrents_offset + viewport {
+                self.torrents_offset = i + 1 - viewport;
+            }
+        }
+        self.torrents_offset = self.torrents_offset.min(self.torrents.len().saturating_sub(viewport));
+        let offset = self.torrents_offset;
+        let end = (offset + viewport).min(self.torrents.len());
+
+        // The torrents are out of date while the server can't be reached.
+        let stale = !self.connection.is_connected() && !self.torrents.is_empty();
         let mut rows = vec![];
-        for (i, torrent) in self.torrents.iter().enumerate() {
-            let color = if i % 2 == 0 {
+        for (i, torrent) in self.torrents[offset..end].iter().enumerate() {
+            let i = offset + i;
+            let color = if i.is_multiple_of(2) {
                 Color::DarkGray
             } else {
                 Color::Black
@@ -207,8 +952,17 @@ impl App {
             let eta = self.format_seconds(torrent.eta.unwrap_or(0));
             let ratio = torrent.ratio.unwrap_or(-1.0);
 
-            let item: Row<'_> = [
-                torrent.name.clone().unwrap_or_else(|| String::from("")),
+            // Mark torrents in the multi-selection.
+            let marked = torrent.hash.as_ref().is_some_and(|hash| self.selection.contains(hash));
+            let name = torrent.name.clone().unwrap_or_else(|| String::from(""));
+            let (name, fg) = if marked {
+                (format!("● {}", name), Color::LightYellow)
+            } else {
+                (name, Color::White)
+            };
+
+            let mut cells = vec![
+                name,
                 size,
                 downloaded,
                 format!("{:.2}%", progress),
@@ -217,17 +971,21 @@ impl App {
                 upspeed,
                 eta,
                 format!("{:.4}", ratio),
-            ]
-            .into_iter()
-            .map(Cell::new)
-            .collect::<Row>()
-            .style(Style::default().fg(Color::White).bg(color))
-            .height(TABLE_ITEM_HEIGHT as u16);
+            ];
+            if show_category {
+                cells.push(torrent.category.clone().unwrap_or_default());
+            }
+            let item: Row<'_> = cells
+                .into_iter()
+                .map(Cell::new)
+                .collect::<Row>()
+                .style(Style::default().fg(if stale { Color::Gray } else { fg }).bg(color))
+                .height(TABLE_ITEM_HEIGHT as u16);
             rows.push(item);
         }
 
-        let witdths = [
-            Constraint::Percentage(27), // Name
+        let mut witdths = vec![
+            Constraint::Percentage(if show_category { 19 } else { 27 }), // Name
             Constraint::Percentage(10), // Size
             Constraint::Percentage(13), // Bytes Downloaded
             Constraint::Percentage(6), // Progress
@@ -237,15 +995,34 @@ impl App {
             Constraint::Percentage(10), // ETA
             Constraint::Percentage(10), // Ratio
         ];
+        if show_category {
+            witdths.push(Constraint::Percentage(8)); // Category
+        }
+
+        // Show a spinner while requests to the API are in flight.
+        let mut block = Block::default().borders(Borders::ALL);
+        if self.pending_requests > 0 {
+            self.spinner_frame = (self.spinner_frame + 1) % SPINNER.len();
+            block = block.title(format!(" {} Loading ", SPINNER[self.spinner_frame]));
+        }
+        if stale {
+            block = block
+                .title(" Stale ".fg(Color::LightRed))
+                .border_style(Style::new().fg(Color::DarkGray));
+        }
 
         let t = Table::new(rows,witdths)
             .header(header)
-            .block(Block::default().borders(Borders::ALL))
+            .block(block)
             .row_highlight_style(selected_row_style)
             .column_highlight_style(selected_col_style)
             .cell_highlight_style(selected_cell_style);
 
-        frame.render_stateful_widget(t, area, &mut self.state);
+        // The rendered rows start at the offset so the selection is made relative to it.
+        let mut visible_state = self.state.clone();
+        *visible_state.offset_mut() = 0;
+        visible_state.select(self.state.selected().map(|i| i - offset));
+        frame.render_stateful_widget(t, area, &mut visible_state);
 
         // Render the scrollbar on the right side of the table
         self.scroll_state = self.scroll_state.content_length(self.torrents.len()).viewport_content_length(TABLE_ITEM_HEIGHT);
@@ -255,7 +1032,7 @@ impl App {
     /// Renders the selection tab for the torrent info section and calls the appropriate render function based on the selected tab.
     pub fn render_torrent_into(&mut self, frame: &mut Frame, area: Rect) {
         let vertical = Layout::vertical(
-            [Constraint::Min(3), Constraint::Length(14)]
+            [Constraint::Min(3), Constraint::Length(15)]
         );
         let rects = vertical.split(area);
         self.render_info_tabs(frame, rects[0]);
@@ -306,7 +1083,7 @@ impl App {
     /// The popup contains a progress bar, torrent transfer info, and file/torrent info.
     fn render_selected_torrent(&self, frame: &mut Frame, area: Rect) {
         let vertical = Layout::vertical(
-            [Constraint::Length(3), Constraint::Length(7), Constraint::Length(4)]
+            [Constraint::Length(3), Constraint::Length(7), Constraint::Length(5)]
         );
         let rects = vertical.split(area);
         let block = Block::bordered().style(Style::new().fg(Color::White).bg(Color::Black));
@@ -353,8 +1130,8 @@ impl App {
         .collect::<Row>();
         rows.push(row_three);
         let row_four: Row<'_> = [
-            format!("Download Limit: {:?}", selected_torrent.dl_limit.unwrap_or(-1)),
-            format!("Upload Limit: {:?}", selected_torrent.up_limit.unwrap_or(-1)),
+            format!("Download Limit: {}", self.format_rate_limit(selected_torrent.dl_limit)),
+            format!("Upload Limit: {}", self.format_rate_limit(selected_torrent.up_limit)),
             format!("Sequential Dl: {:?}", selected_torrent.seq_dl.unwrap())
         ]
         .into_iter()
@@ -400,6 +1177,15 @@ impl App {
         .map(Cell::new)
         .collect::<Row>();
         rows_two.push(row_two);
+        let row_three = [
+            format!("Ratio Limit: {}", self.format_ratio_limit(selected_torrent.ratio_limit)),
+            format!("Seeding Time Limit: {}", self.format_seeding_time_limit(selected_torrent.seeding_time_limit)),
+            format!("Category: {}", selected_torrent.category.clone().unwrap_or_default())
+        ]
+        .into_iter()
+        .map(Cell::new)
+        .collect::<Row>();
+        rows_two.push(row_three);
         let t = Table::new(rows_two, widths)
         .block(block.clone().title("Information").title_alignment(Alignment::Center));
         frame.render_widget(t, rects[2]);
@@ -420,35 +1206,66 @@ impl App {
             .style(Style::default().bold().fg(Color::White).bg(Color::Black))
             .height(1);
         let mut rows = vec![];
-        for file in self.torrent_content.iter() {
+        for row in self.file_rows.iter().map(|i| &self.file_tree[*i]) {
+            let files: Vec<&TorrentContent> = row.files.iter().map(|i| &self.torrent_content[*i]).collect();
+            // Folders show the total size, the progress weighted by size and the priority their files share.
+            let size: u64 = files.iter().map(|file| file.size).sum();
+            let progress = if size == 0 {
+                files.iter().map(|file| file.progress).sum::<f64>() / files.len().max(1) as f64
+            } else {
+                files.iter().map(|file| file.progress * file.size as f64).sum::<f64>() / size as f64
+            };
+            let priority = match files.split_first() {
+                Some((first, rest)) if rest.iter().all(|file| file.priority == first.priority) => first.priority,
+                _ => Priority::Mixed,
+            };
+            let indent = "  ".repeat(row.depth);
+            let name = match row.file {
+                Some(_) => format!("{}{}", indent, row.label),
+                None if self.collapsed_folders.contains(&row.path) => format!("{}▸ {}/", indent, row.label),
+                None => format!("{}▾ {}/", indent, row.label),
+            };
+            // Files in the multi-selection are marked like selected torrents, folders when all their files are.
+            let (name, color) = if files.iter().all(|file| self.file_selection.contains(&file.index)) {
+                (format!("● {}", name), Color::LightYellow)
+            } else {
+                (name, Color::White)
+            };
             let item: Row<'_> = [
-                file.name.clone(),
-                self.format_priority(file.priority),
-                self.format_bytes(file.size as i64),
-                format!("{:.2}%", file.progress * 100.0),
+                name,
+                self.format_priority(priority),
+                self.format_bytes(size as i64),
+                format!("{:.2}%", progress * 100.0),
             ]
             .into_iter()
             .map(Cell::new)
             .collect::<Row>()
-            .style(Style::default().fg(Color::White).bg(Color::Black));
+            .style(Style::default().fg(color).bg(Color::Black));
             rows.push(item);
         }
         let widths = [
-            Constraint::Percentage(50), // Name
-            Constraint::Percentage(20), // Priority
-            Constraint::Percentage(20), // Size
-            Constraint::Percentage(10), // Progress
+            Constraint::Percentage(55), // Name
+            Constraint::Percentage(15), // Priority
+            Constraint::Percentage(15), // Size
+            Constraint::Percentage(15), // Progress
         ];
+        let title = match self.file_selection.len() {
+            0 => " (Space) select | (0/1/2/3) skip/normal/high/max | (Enter) fold | (+/-) expand/collapse all ".to_string(),
+            n => format!(" {} selected | (0/1/2/3) skip/normal/high/max | (Enter) fold | (+/-) expand/collapse all ", n),
+        };
         let t = Table::new(rows, widths)
             .header(header)
-            .block(Block::default().borders(Borders::ALL))
+            .block(Block::default().borders(Borders::ALL).title_bottom(title))
             .row_highlight_style(selected_row_style);
         frame.render_stateful_widget(t, area, &mut self.info_tab_state);
 
+        // Files are always navigated so their priority can be changed, even when they all fit.
+        let file_count = self.file_rows.len();
+        if file_count > 0 {
+            self.info_tab_scrollbar(file_count, INFO_TAB_DETAILS);
+        }
         // Render the scrollbar on the right side of the table if there are more than INFO_TAB_DETAILS files.
-        let file_count = self.torrent_content.len();
         if file_count > INFO_TAB_DETAILS {
-            self.info_tab_scrollbar(file_count, INFO_TAB_DETAILS);
             frame.render_stateful_widget(Scrollbar::new(ScrollbarOrientation::VerticalRight), area, &mut self.info_tab_scroll_state);
         }
     }
@@ -508,7 +1325,6 @@ impl App {
 
     /// Renders the curent peers returned by the qBittorrent API.
     fn render_torrent_peers(&mut self, frame: &mut Frame, area: Rect) {
-        // TODO: Allow user selected sorting of the table.
         let selected_row_style = Style::default()
             .add_modifier(Modifier::BOLD)
             .bg(Color::LightBlue)
@@ -1,4 +1,6 @@
 use crate::Message;
+use qbit_rs::model::{State, Torrent};
+use serde::{Serialize, Deserialize};
 
 #[derive(Debug, Default)]
 pub enum ScrollContext {
@@ -80,4 +82,370 @@ impl SelectedAddTorrentTab {
             SelectedAddTorrentTab::File => *self = SelectedAddTorrentTab::MagnetLink,
         };
     }
-}
\ No newline at end of file
+}
+
+/// Field being edited in the global speed limits popup.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum SpeedLimitField {
+    #[default]
+    Download,
+    Upload,
+}
+
+impl SpeedLimitField {
+    pub fn toggle(&mut self) {
+        match self {
+            SpeedLimitField::Download => *self = SpeedLimitField::Upload,
+            SpeedLimitField::Upload => *self = SpeedLimitField::Download,
+        };
+    }
+}
+
+/// Field being edited in the torrent limits popup.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum TorrentLimitField {
+    #[default]
+    Download,
+    Upload,
+    Ratio,
+    SeedingTime,
+}
+
+impl TorrentLimitField {
+    const ALL: [TorrentLimitField; 4] = [
+        TorrentLimitField::Download,
+        TorrentLimitField::Upload,
+        TorrentLimitField::Ratio,
+        TorrentLimitField::SeedingTime,
+    ];
+
+    pub fn to_index(self) -> usize {
+        Self::ALL.iter().position(|field| *field == self).unwrap_or(0)
+    }
+
+    /// Move to the next or previous field, wrapping around at either end.
+    pub fn shift(&mut self, delta: isize) {
+        let count = Self::ALL.len() as isize;
+        let index = (self.to_index() as isize + delta).rem_euclid(count) as usize;
+        *self = Self::ALL[index];
+    }
+}
+
+/// Layout of the content of an added torrent, sent as root_folder.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ContentLayout {
+    /// Keep the layout of the torrent.
+    #[default]
+    Original,
+    /// Always create a root folder.
+    Subfolder,
+    /// Never create a root folder.
+    NoSubfolder,
+}
+
+impl ContentLayout {
+    pub fn label(self) -> &'static str {
+        match self {
+            ContentLayout::Original => "Original",
+            ContentLayout::Subfolder => "Create subfolder",
+            ContentLayout::NoSubfolder => "Don't create subfolder",
+        }
+    }
+
+    /// The root_folder value for the add torrent request, None keeps the layout of the torrent.
+    pub fn root_folder(self) -> Option<String> {
+        match self {
+            ContentLayout::Original => None,
+            ContentLayout::Subfolder => Some("true".to_string()),
+            ContentLayout::NoSubfolder => Some("false".to_string()),
+        }
+    }
+
+    pub fn cycle(&mut self) {
+        *self = match self {
+            ContentLayout::Original => ContentLayout::Subfolder,
+            ContentLayout::Subfolder => ContentLayout::NoSubfolder,
+            ContentLayout::NoSubfolder => ContentLayout::Original,
+        };
+    }
+}
+
+/// Field being edited in the add torrent options form.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum AddOptionField {
+    #[default]
+    SavePath,
+    Category,
+    Tags,
+    Rename,
+    DownloadLimit,
+    UploadLimit,
+    Paused,
+    SkipChecking,
+    ContentLayout,
+    SequentialDownload,
+    FirstLastPiecePriority,
+}
+
+impl AddOptionField {
+    pub const ALL: [AddOptionField; 11] = [
+        AddOptionField::SavePath,
+        AddOptionField::Category,
+        AddOptionField::Tags,
+        AddOptionField::Rename,
+        AddOptionField::DownloadLimit,
+        AddOptionField::UploadLimit,
+        AddOptionField::Paused,
+        AddOptionField::SkipChecking,
+        AddOptionField::ContentLayout,
+        AddOptionField::SequentialDownload,
+        AddOptionField::FirstLastPiecePriority,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AddOptionField::SavePath => "Save path",
+            AddOptionField::Category => "Category",
+            AddOptionField::Tags => "Tags",
+            AddOptionField::Rename => "Rename",
+            AddOptionField::DownloadLimit => "Download limit",
+            AddOptionField::UploadLimit => "Upload limit",
+            AddOptionField::Paused => "Add paused",
+            AddOptionField::SkipChecking => "Skip hash check",
+            AddOptionField::ContentLayout => "Content layout",
+            AddOptionField::SequentialDownload => "Sequential download",
+            AddOptionField::FirstLastPiecePriority => "First/last piece first",
+        }
+    }
+
+    /// Returns true for fields edited by typing, the others are toggled or cycled.
+    pub fn is_text(self) -> bool {
+        matches!(self, AddOptionField::SavePath | AddOptionField::Tags | AddOptionField::Rename
+            | AddOptionField::DownloadLimit | AddOptionField::UploadLimit)
+    }
+
+    pub fn to_index(self) -> usize {
+        Self::ALL.iter().position(|field| *field == self).unwrap_or(0)
+    }
+
+    /// Move to the next or previous field, wrapping around at either end.
+    pub fn shift(&mut self, delta: isize) {
+        let count = Self::ALL.len() as isize;
+        let index = (self.to_index() as isize + delta).rem_euclid(count) as usize;
+        *self = Self::ALL[index];
+    }
+}
+
+/// Column the torrents table is sorted by.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SortColumn {
+    Name,
+    Size,
+    Downloaded,
+    Progress,
+    State,
+    DlSpeed,
+    UpSpeed,
+    Eta,
+    Ratio,
+}
+
+impl SortColumn {
+    const ALL: [SortColumn; 9] = [
+        SortColumn::Name,
+        SortColumn::Size,
+        SortColumn::Downloaded,
+        SortColumn::Progress,
+        SortColumn::State,
+        SortColumn::DlSpeed,
+        SortColumn::UpSpeed,
+        SortColumn::Eta,
+        SortColumn::Ratio,
+    ];
+
+    /// Index of the column in the torrents table header.
+    pub fn to_index(self) -> usize {
+        Self::ALL.iter().position(|column| *column == self).unwrap()
+    }
+
+    /// Cycle to the next sort column.
+    /// Wraps around to None (the order torrents were added) after the last column.
+    pub fn cycle(column: Option<Self>) -> Option<Self> {
+        match column {
+            None => Some(Self::ALL[0]),
+            Some(column) => Self::ALL.get(column.to_index() + 1).copied(),
+        }
+    }
+}
+
+/// Torrent state filters shown in the sidebar, matching the qBittorrent WebUI.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum StatusFilter {
+    #[default]
+    All,
+    Downloading,
+    Seeding,
+    Completed,
+    Paused,
+    Active,
+    Inactive,
+    Stalled,
+    Checking,
+    Errored,
+}
+
+impl StatusFilter {
+    pub const ALL: [StatusFilter; 10] = [
+        StatusFilter::All,
+        StatusFilter::Downloading,
+        StatusFilter::Seeding,
+        StatusFilter::Completed,
+        StatusFilter::Paused,
+        StatusFilter::Active,
+        StatusFilter::Inactive,
+        StatusFilter::Stalled,
+        StatusFilter::Checking,
+        StatusFilter::Errored,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StatusFilter::All => "All",
+            StatusFilter::Downloading => "Downloading",
+            StatusFilter::Seeding => "Seeding",
+            StatusFilter::Completed => "Completed",
+            StatusFilter::Paused => "Paused",
+            StatusFilter::Active => "Active",
+            StatusFilter::Inactive => "Inactive",
+            StatusFilter::Stalled => "Stalled",
+            StatusFilter::Checking => "Checking",
+            StatusFilter::Errored => "Errored",
+        }
+    }
+
+    /// Returns true if the torrent state belongs to the filter.
+    pub fn matches(self, torrent: &Torrent) -> bool {
+        let active = torrent.dlspeed.unwrap_or(0) > 0 || torrent.upspeed.unwrap_or(0) > 0;
+        match self {
+            StatusFilter::All => true,
+            StatusFilter::Downloading => matches!(torrent.state, Some(
+                State::Downloading | State::MetaDL | State::ForcedDL | State::StalledDL
+                | State::CheckingDL | State::PausedDL | State::QueuedDL)),
+            StatusFilter::Seeding => matches!(torrent.state, Some(
+                State::Uploading | State::ForcedUP | State::StalledUP | State::QueuedUP | State::CheckingUP)),
+            StatusFilter::Completed => matches!(torrent.state, Some(
+                State::Uploading | State::ForcedUP | State::StalledUP | State::QueuedUP
+                | State::CheckingUP | State::PausedUP)),
+            StatusFilter::Paused => matches!(torrent.state, Some(State::PausedDL | State::PausedUP)),
+            StatusFilter::Active => active,
+            StatusFilter::Inactive => !active,
+            StatusFilter::Stalled => matches!(torrent.state, Some(State::StalledDL | State::StalledUP)),
+            StatusFilter::Checking => matches!(torrent.state, Some(
+                State::CheckingDL | State::CheckingUP | State::CheckingResumeData)),
+            StatusFilter::Errored => matches!(torrent.state, Some(State::Error | State::MissingFiles)),
+        }
+    }
+}
+
+/// Entries listed in the filter sidebar.
+/// For categories, tags and trackers None is every torrent and an empty string is torrents without one.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SidebarEntry {
+    Header(&'static str),
+    Status(StatusFilter),
+    Category(Option<String>),
+    Tag(Option<String>),
+    Tracker(Option<String>),
+}
+
+/// Severity of a notification, which sets the colour of its toast.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NotificationLevel {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+/// State of the connection to the qBittorrent server.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum ConnectionState {
+    #[default]
+    Connecting,
+    /// Connected with the qBittorrent and WebAPI versions of the server.
+    Connected { version: String, api_version: String },
+    /// The server rejected the username or password.
+    AuthFailed,
+    /// The server banned the IP for too many failed logins, retrying would extend the ban.
+    Banned,
+    /// The server couldn't be reached, the sync is retried with a back off.
+    Unreachable,
+}
+
+impl ConnectionState {
+    /// Returns the connection state for an error from the API.
+    pub fn from_error(err: &qbit_rs::Error) -> Self {
+        match err {
+            // qBittorrent doesn't set a cookie when the login is rejected.
+            qbit_rs::Error::BadResponse { .. } => ConnectionState::AuthFailed,
+            qbit_rs::Error::ApiError(qbit_rs::ApiError::IpBanned) => ConnectionState::Banned,
+            qbit_rs::Error::HttpError(err)
+                if err.status().is_some_and(|status| matches!(status.as_u16(), 401 | 403)) => ConnectionState::AuthFailed,
+            _ => ConnectionState::Unreachable,
+        }
+    }
+
+    pub fn is_connected(&self) -> bool {
+        matches!(self, ConnectionState::Connected { .. })
+    }
+}
+
+/// What the rename prompt renames, with the path of files and folders before the rename.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenameTarget {
+    Torrent { hash: String },
+    File { hash: String, path: String },
+    Folder { hash: String, path: String },
+}
+
+impl RenameTarget {
+    /// Returns the new path of a file or folder renamed to the given name, keeping its parent folder.
+    pub fn renamed_path(path: &str, name: &str) -> String {
+        match path.rsplit_once('/') {
+            Some((parent, _)) => format!("{}/{}", parent, name),
+            None => name.to_string(),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RenameTarget::Torrent { .. } => "torrent",
+            RenameTarget::File { .. } => "file",
+            RenameTarget::Folder { .. } => "folder",
+        }
+    }
+}
+
+/// Whether the form in the categories manager adds a category or edits the save path of an existing one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CategoryForm {
+    Add,
+    Edit,
+}
+
+/// Field being edited in the categories manager form.
+/// Existing categories can't be renamed, so only the save path is edited for them.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum CategoryField {
+    #[default]
+    Name,
+    SavePath,
+}
+
+impl CategoryField {
+    pub fn toggle(&mut self) {
+        match self {
+            CategoryField::Name => *self = CategoryField::SavePath,
+            CategoryField::SavePath => *self = CategoryField::Name,
+        };
+    }
+}
@@ -0,0 +1,74 @@
+use std::collections::HashSet;
+
+/// A row of a file tree, either a folder or a file.
+#[derive(Debug, Clone)]
+pub struct TreeRow {
+    pub depth: usize,
+    pub label: String,
+    /// Full path of the folder or file.
+    pub path: String,
+    /// Index of the file, None for folders.
+    pub file: Option<usize>,
+    /// Indexes of the files the row covers, a folder covers every file under it.
+    pub files: Vec<usize>,
+}
+
+impl TreeRow {
+    pub fn is_folder(&self) -> bool {
+        self.file.is_none()
+    }
+}
+
+/// Build the rows of the file tree from '/' separated paths, listing the folders of each file before it.
+/// Rows refer to files by their position in the given paths.
+pub fn tree_rows(paths: &[&str]) -> Vec<TreeRow> {
+    let mut order: Vec<usize> = (0..paths.len()).collect();
+    order.sort_by(|a, b| paths[*a].cmp(paths[*b]));
+    let mut rows: Vec<TreeRow> = vec![];
+    // Path of each open folder and the row it was added at.
+    let mut folders: Vec<(String, usize)> = vec![];
+    for i in order {
+        let parts: Vec<&str> = paths[i].split('/').collect();
+        let (name, dirs) = parts.split_last().expect("split always returns a part");
+        // Close folders that aren't a parent of this file.
+        let mut depth = 0;
+        while depth < folders.len() && depth < dirs.len() && folders[depth].0 == dirs[..=depth].join("/") {
+            depth += 1;
+        }
+        folders.truncate(depth);
+        for (depth, dir) in dirs.iter().enumerate().skip(depth) {
+            let path = dirs[..=depth].join("/");
+            folders.push((path.clone(), rows.len()));
+            rows.push(TreeRow { depth, label: dir.to_string(), path, file: None, files: vec![] });
+        }
+        for (_, row) in folders.iter() {
+            rows[*row].files.push(i);
+        }
+        rows.push(TreeRow {
+            depth: dirs.len(),
+            label: name.to_string(),
+            path: paths[i].to_string(),
+            file: Some(i),
+            files: vec![i],
+        });
+    }
+    rows
+}
+
+/// Returns the positions of the rows that aren't inside a collapsed folder.
+pub fn visible_rows(rows: &[TreeRow], collapsed: &HashSet<String>) -> Vec<usize> {
+    let mut visible = vec![];
+    // Depth of the collapsed folder whose rows are being skipped.
+    let mut hidden_below: Option<usize> = None;
+    for (i, row) in rows.iter().enumerate() {
+        match hidden_below {
+            Some(depth) if row.depth > depth => continue,
+            _ => hidden_below = None,
+        }
+        visible.push(i);
+        if row.is_folder() && collapsed.contains(&row.path) {
+            hidden_below = Some(row.depth);
+        }
+    }
+    visible
+}
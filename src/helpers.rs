@@ -1,6 +1,87 @@
-use crate::{signals::Message, App, SelectedInfoTab, ScrollContext};
+use crate::{
+    elements::INFO_TAB_DETAILS, file_tree::{tree_rows, visible_rows}, enums::{AddOptionField, CategoryField, CategoryForm, ConnectionState, NotificationLevel, RenameTarget, SidebarEntry, StatusFilter}, signals::Message, 
+    App, InputMode, SelectedInfoTab, ScrollContext, SortColumn
+};
 use ratatui::layout::{Constraint, Flex, Layout, Rect};
 use chrono::DateTime;
+use qbit_rs::model::{Category, RatioLimit, SeedingTimeLimit, Torrent};
+use regex::RegexBuilder;
+use std::{cmp::Ordering, collections::{HashMap, HashSet}, time::Duration};
+
+/// Upper bound in seconds for the refresh back off when the server is unreachable.
+const MAX_REFRESH_BACKOFF: u64 = 300;
+/// Seconds between background refreshes while a torrent is being moved, so the move is followed closely.
+const MOVING_REFRESH_INTERVAL: u64 = 1;
+
+/// Returns the host of a tracker URL, used to group trackers in the sidebar.
+fn tracker_host(url: &str) -> String {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    without_scheme
+        .split(['/', ':', '?'])
+        .next()
+        .unwrap_or(without_scheme)
+        .to_string()
+}
+
+/// Parse a rate limit such as "5M", "500K" or "1.5 MiB/s" into bytes/s.
+/// Units are powers of 1024 to match [`App::format_rate`], a number without a unit is in bytes/s.
+/// An empty input, 0 or "unlimited" is no limit and returns 0.
+pub fn parse_rate(input: &str) -> color_eyre::Result<u64> {
+    let input = input.trim().to_lowercase();
+    if input.is_empty() || input == "unlimited" || input == "∞" {
+        return Ok(0);
+    }
+    let unit_start = input.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(input.len());
+    let (number, unit) = input.split_at(unit_start);
+    let number: f64 = number.parse()
+        .map_err(|_| color_eyre::eyre::eyre!("Invalid rate \"{}\", expected a number like 5M or 500K", input))?;
+    let unit = unit.trim().trim_end_matches("/s").trim_end_matches("ib").trim_end_matches('b');
+    let multiplier: u64 = match unit {
+        "" => 1,
+        "k" => 1 << 10,
+        "m" => 1 << 20,
+        "g" => 1 << 30,
+        "t" => 1 << 40,
+        _ => return Err(color_eyre::eyre::eyre!("Invalid unit \"{}\", expected K, M, G or T", unit)),
+    };
+    Ok((number * multiplier as f64).round() as u64)
+}
+
+/// Parse a ratio limit, "global" or an empty input uses the global ratio limit.
+pub fn parse_ratio_limit(input: &str) -> color_eyre::Result<RatioLimit> {
+    let input = input.trim().to_lowercase();
+    match input.as_str() {
+        "" | "global" => Ok(RatioLimit::Global),
+        "unlimited" | "∞" => Ok(RatioLimit::NoLimit),
+        _ => match input.parse::<f64>() {
+            Ok(ratio) if ratio >= 0.0 => Ok(RatioLimit::Limited(ratio)),
+            _ => Err(color_eyre::eyre::eyre!("Invalid ratio limit \"{}\", expected a number, global or unlimited", input)),
+        },
+    }
+}
+
+/// Parse a seeding time limit in minutes, or hours and days with an h or d suffix.
+/// "global" or an empty input uses the global seeding time limit.
+pub fn parse_seeding_time_limit(input: &str) -> color_eyre::Result<SeedingTimeLimit> {
+    let input = input.trim().to_lowercase();
+    match input.as_str() {
+        "" | "global" => return Ok(SeedingTimeLimit::Global),
+        "unlimited" | "∞" => return Ok(SeedingTimeLimit::NoLimit),
+        _ => {}
+    }
+    let (number, multiplier) = match input.strip_suffix('d') {
+        Some(days) => (days, 24 * 60),
+        None => match input.strip_suffix('h') {
+            Some(hours) => (hours, 60),
+            None => (input.strip_suffix('m').unwrap_or(&input), 1),
+        },
+    };
+    // Times too large to count in minutes are as invalid as text.
+    match number.trim().parse::<u64>().ok().and_then(|time| time.checked_mul(multiplier)) {
+        Some(minutes) => Ok(SeedingTimeLimit::Limited(minutes)),
+        None => Err(color_eyre::eyre::eyre!("Invalid seeding time \"{}\", expected minutes like 90, 12h or 7d", input)),
+    }
+}
 
 impl App {
     /// Takes the torrent state returned from qbittorrent api and converts it to a human readable string.
@@ -74,7 +155,7 @@ impl App {
         match self.info_tab {
             SelectedInfoTab::Trackers => self.torrent_trackers.len(),
             SelectedInfoTab::Peers => self.torrent_peers.as_ref().unwrap().peers.as_ref().unwrap().len(),
-            SelectedInfoTab::Files => self.torrent_content.len(),
+            SelectedInfoTab::Files => self.file_rows.len(),
             SelectedInfoTab::Details => 0 // Details tab does not have elements 
         }
     }
@@ -84,6 +165,9 @@ impl App {
     pub fn scroll_down(&mut self) -> Option<Message> {
         match self.scroll_context {
             ScrollContext::TorrentsTable => {
+                if self.torrents.is_empty() {
+                    return None;
+                }
                 let i =  match self.state.selected() {
                     Some(i) => {
                         if i >= self.torrents.len() - 1 {
@@ -94,16 +178,16 @@ impl App {
                     }
                     None => 0,
                 };
-                self.state.select(Some(i));
-                self.scroll_state = self.scroll_state.position(i);
-                if self.torrent_popup {
-                    return self.info_tab.update_selected();
-                }
+                return self.select_torrent(i);
             },
             ScrollContext::InfoTab => {
+                let length = self.info_tab_elements_length();
+                if length == 0 {
+                    return None;
+                }
                 let i = match self.info_tab_state.selected() {
                     Some(i) => {
-                        if i >= self.info_tab_elements_length() - 1 {
+                        if i >= length - 1 {
                             0
                         } else {
                             i + 1
@@ -111,8 +195,7 @@ impl App {
                     }
                     None => 0,
                 };
-                self.info_tab_state.select(Some(i));
-                self.info_tab_scroll_state = self.info_tab_scroll_state.position(i);
+                self.select_info_tab_row(i);
             }
         }
         None
@@ -123,7 +206,9 @@ impl App {
     pub fn scroll_up(&mut self) -> Option<Message>{
         match self.scroll_context {
             ScrollContext::TorrentsTable => {
-
+                if self.torrents.is_empty() {
+                    return None;
+                }
                 let i = match self.state.selected() {
                     Some(i) => {
                         if i == 0 {
@@ -134,30 +219,658 @@ impl App {
                     }
                     None => 0,
                 };
-                self.state.select(Some(i));
-                self.scroll_state = self.scroll_state.position(i);
-                if self.torrent_popup {
-                    return self.info_tab.update_selected();
-                }
+                return self.select_torrent(i);
             },
             ScrollContext::InfoTab => {
+                let length = self.info_tab_elements_length();
+                if length == 0 {
+                    return None;
+                }
                 let i = match self.info_tab_state.selected() {
                     Some(i) => {
                         if i == 0 {
-                            self.info_tab_elements_length() - 1
+                            length - 1
                         } else {
                             i - 1
                         }
                     }
                     None => 0,
                 };
-                self.info_tab_state.select(Some(i));
-                self.info_tab_scroll_state = self.info_tab_scroll_state.position(i);
+                self.select_info_tab_row(i);
             }
         }
         None            
     }
 
+    /// Move the selection by a page in the current ScrollContext without wrapping around.
+    /// A negative number of pages moves the selection up.
+    pub fn scroll_page(&mut self, pages: isize) -> Option<Message> {
+        let (length, selected, viewport) = match self.scroll_context {
+            ScrollContext::TorrentsTable => (self.torrents.len(), self.state.selected(), self.torrents_viewport),
+            ScrollContext::InfoTab => (self.info_tab_elements_length(), self.info_tab_state.selected(), INFO_TAB_DETAILS),
+        };
+        if length == 0 {
+            return None;
+        }
+        let delta = pages * viewport.max(1) as isize;
+        let i = (selected.unwrap_or(0) as isize + delta).clamp(0, length as isize - 1) as usize;
+        self.scroll_to(i)
+    }
+
+    /// Move the selection to the given index in the current ScrollContext.
+    /// Indexes past the end of the list select the last element.
+    pub fn scroll_to(&mut self, index: usize) -> Option<Message> {
+        match self.scroll_context {
+            ScrollContext::TorrentsTable => {
+                if self.torrents.is_empty() {
+                    return None;
+                }
+                return self.select_torrent(index.min(self.torrents.len() - 1));
+            },
+            ScrollContext::InfoTab => {
+                let length = self.info_tab_elements_length();
+                if length > 0 {
+                    self.select_info_tab_row(index.min(length - 1));
+                }
+            }
+        }
+        None
+    }
+
+    /// Select the torrent at the given index and refresh the torrent info popup if it is shown.
+    fn select_torrent(&mut self, i: usize) -> Option<Message> {
+        self.state.select(Some(i));
+        self.scroll_state = self.scroll_state.position(i);
+        self.update_range_selection();
+        if self.torrent_popup {
+            return self.info_tab.update_selected();
+        }
+        None
+    }
+
+    /// Select the row at the given index in the current info tab.
+    fn select_info_tab_row(&mut self, i: usize) {
+        self.info_tab_state.select(Some(i));
+        self.info_tab_scroll_state = self.info_tab_scroll_state.position(i);
+    }
+
+    /// Rebuild the displayed torrents from the synced torrents.
+    /// Applies the search filter and sort while keeping the selection on the same torrent.
+    pub fn rebuild_torrents(&mut self) {
+        let selected = self.selected_hash();
+        let hosts = self.tracker_hosts();
+        let mut torrents = self.torrent_sync.torrents();
+        torrents.retain(|torrent| self.search_matches(torrent) && self.sidebar_matches(torrent, &hosts));
+        self.torrents = torrents;
+        self.update_sidebar(&hosts);
+        self.sort_torrents();
+        self.restore_selection(selected);
+    }
+
+    /// Returns the torrent hashes keyed by tracker host.
+    /// Falls back to the working tracker of each torrent for servers that don't sync trackers.
+    fn tracker_hosts(&self) -> HashMap<String, HashSet<String>> {
+        let mut hosts: HashMap<String, HashSet<String>> = HashMap::new();
+        let trackers = self.torrent_sync.trackers();
+        if trackers.is_empty() {
+            for torrent in self.torrent_sync.iter() {
+                if let (Some(tracker), Some(hash)) = (torrent.tracker.as_ref(), torrent.hash.as_ref())
+                    && !tracker.is_empty() {
+                    hosts.entry(tracker_host(tracker)).or_default().insert(hash.clone());
+                }
+            }
+        }
+        for (url, hashes) in trackers {
+            hosts.entry(tracker_host(url)).or_default().extend(hashes.iter().cloned());
+        }
+        hosts
+    }
+
+    /// Returns true if the torrent is included by the sidebar entry.
+    fn entry_matches(&self, entry: &SidebarEntry, torrent: &Torrent, hosts: &HashMap<String, HashSet<String>>) -> bool {
+        match entry {
+            SidebarEntry::Header(_) => false,
+            SidebarEntry::Status(status) => status.matches(torrent),
+            SidebarEntry::Category(None) | SidebarEntry::Tag(None) | SidebarEntry::Tracker(None) => true,
+            SidebarEntry::Category(Some(category)) => torrent.category.as_deref().unwrap_or("") == category,
+            SidebarEntry::Tag(Some(tag)) => {
+                let mut tags = torrent.tags.as_deref().unwrap_or("")
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty());
+                if tag.is_empty() {
+                    tags.next().is_none()
+                } else {
+                    tags.any(|t| t == tag)
+                }
+            },
+            SidebarEntry::Tracker(Some(host)) => {
+                let hash = torrent.hash.as_deref().unwrap_or("");
+                if host.is_empty() {
+                    !hosts.values().any(|hashes| hashes.contains(hash))
+                } else {
+                    hosts.get(host).is_some_and(|hashes| hashes.contains(hash))
+                }
+            },
+        }
+    }
+
+    /// Returns true if the torrent is included by every active sidebar filter.
+    fn sidebar_matches(&self, torrent: &Torrent, hosts: &HashMap<String, HashSet<String>>) -> bool {
+        [
+            SidebarEntry::Status(self.status_filter),
+            SidebarEntry::Category(self.category_filter.clone()),
+            SidebarEntry::Tag(self.tag_filter.clone()),
+            SidebarEntry::Tracker(self.tracker_filter.clone()),
+        ]
+        .iter()
+        .all(|entry| self.entry_matches(entry, torrent, hosts))
+    }
+
+    /// Rebuild the sidebar entries and the number of torrents matching each of them.
+    fn update_sidebar(&mut self, hosts: &HashMap<String, HashSet<String>>) {
+        if !self.sidebar_popup {
+            return;
+        }
+        let mut entries = vec![SidebarEntry::Header("Status")];
+        entries.extend(StatusFilter::ALL.into_iter().map(SidebarEntry::Status));
+        entries.push(SidebarEntry::Header("Categories"));
+        entries.push(SidebarEntry::Category(None));
+        entries.push(SidebarEntry::Category(Some(String::new())));
+        let mut categories: Vec<&String> = self.torrent_sync.categories().keys().collect();
+        categories.sort();
+        entries.extend(categories.into_iter().map(|category| SidebarEntry::Category(Some(category.clone()))));
+        entries.push(SidebarEntry::Header("Tags"));
+        entries.push(SidebarEntry::Tag(None));
+        entries.push(SidebarEntry::Tag(Some(String::new())));
+        entries.extend(self.torrent_sync.tags().iter().map(|tag| SidebarEntry::Tag(Some(tag.clone()))));
+        entries.push(SidebarEntry::Header("Trackers"));
+        entries.push(SidebarEntry::Tracker(None));
+        entries.push(SidebarEntry::Tracker(Some(String::new())));
+        let mut tracker_hosts: Vec<&String> = hosts.keys().collect();
+        tracker_hosts.sort();
+        entries.extend(tracker_hosts.into_iter().map(|host| SidebarEntry::Tracker(Some(host.clone()))));
+
+        self.sidebar_entries = entries.into_iter()
+            .map(|entry| {
+                let count = self.torrent_sync.iter().filter(|torrent| self.entry_matches(&entry, torrent, hosts)).count();
+                (entry, count)
+            })
+            .collect();
+        let last = self.sidebar_entries.len().saturating_sub(1);
+        if self.sidebar_state.selected().is_none_or(|i| i > last) {
+            self.sidebar_state.select(Some(1));
+        }
+    }
+
+    /// Returns true if the sidebar entry is the active filter of its section.
+    pub fn sidebar_entry_active(&self, entry: &SidebarEntry) -> bool {
+        match entry {
+            SidebarEntry::Header(_) => false,
+            SidebarEntry::Status(status) => *status == self.status_filter,
+            SidebarEntry::Category(category) => *category == self.category_filter,
+            SidebarEntry::Tag(tag) => *tag == self.tag_filter,
+            SidebarEntry::Tracker(host) => *host == self.tracker_filter,
+        }
+    }
+
+    /// Move the sidebar cursor by delta entries, skipping the section headers.
+    pub fn sidebar_move(&mut self, delta: isize) {
+        let len = self.sidebar_entries.len() as isize;
+        if len == 0 {
+            return;
+        }
+        let mut i = self.sidebar_state.selected().unwrap_or(0) as isize;
+        for _ in 0..len {
+            i = (i + delta).rem_euclid(len);
+            if !matches!(self.sidebar_entries[i as usize].0, SidebarEntry::Header(_)) {
+                break;
+            }
+        }
+        self.sidebar_state.select(Some(i as usize));
+    }
+
+    /// Make the highlighted sidebar entry the active filter of its section.
+    pub fn apply_sidebar_entry(&mut self) {
+        let Some((entry, _)) = self.sidebar_state.selected().and_then(|i| self.sidebar_entries.get(i)) else {
+            return;
+        };
+        match entry.clone() {
+            SidebarEntry::Header(_) => return,
+            SidebarEntry::Status(status) => self.status_filter = status,
+            SidebarEntry::Category(category) => self.category_filter = category,
+            SidebarEntry::Tag(tag) => self.tag_filter = tag,
+            SidebarEntry::Tracker(host) => self.tracker_filter = host,
+        }
+        self.rebuild_torrents();
+    }
+
+    /// Compile the search query, as a case-insensitive regex or an escaped substring.
+    /// An invalid regex leaves the pattern unset and the torrents unfiltered.
+    pub fn compile_search(&mut self) {
+        let pattern = if self.search_regex {
+            self.search_query.clone()
+        } else {
+            regex::escape(&self.search_query)
+        };
+        self.search_pattern = RegexBuilder::new(&pattern)
+            .case_insensitive(true)
+            .build()
+            .ok();
+    }
+
+    /// Returns true if the torrent name matches the current search.
+    fn search_matches(&self, torrent: &Torrent) -> bool {
+        if self.search_query.is_empty() {
+            return true;
+        }
+        match &self.search_pattern {
+            Some(pattern) => pattern.is_match(torrent.name.as_deref().unwrap_or("")),
+            None => true,
+        }
+    }
+
+    /// Jump to the next search result, wrapping around at the end.
+    /// The table only lists the torrents matching the search, so every row is a result.
+    pub fn next_match(&mut self) -> Option<Message> {
+        let len = self.torrents.len();
+        if len == 0 {
+            return None;
+        }
+        let next = self.state.selected().map_or(0, |i| (i + 1) % len);
+        self.select_torrent(next)
+    }
+
+    /// Jump to the previous search result, wrapping around at the start.
+    pub fn previous_match(&mut self) -> Option<Message> {
+        let len = self.torrents.len();
+        if len == 0 {
+            return None;
+        }
+        let previous = self.state.selected().map_or(len - 1, |i| (i + len - 1) % len);
+        self.select_torrent(previous)
+    }
+
+    /// Sort the torrents by the configured sort column and direction.
+    /// Sorting happens locally so it works with the incremental sync.
+    pub fn sort_torrents(&mut self) {
+        let Some(column) = self.cfg.sort_column else {
+            // Keep the order torrents were added in.
+            if self.cfg.sort_reverse {
+                self.torrents.reverse();
+            }
+            return;
+        };
+        let mut torrents = std::mem::take(&mut self.torrents);
+        torrents.sort_by(|a, b| {
+            let ordering = match column {
+                SortColumn::Name => a.name.as_ref().map(|n| n.to_lowercase())
+                    .cmp(&b.name.as_ref().map(|n| n.to_lowercase())),
+                SortColumn::Size => a.size.cmp(&b.size),
+                SortColumn::Downloaded => a.downloaded.cmp(&b.downloaded),
+                SortColumn::Progress => a.progress.partial_cmp(&b.progress).unwrap_or(Ordering::Equal),
+                SortColumn::State => self.get_torrent_state(a.state.clone())
+                    .cmp(&self.get_torrent_state(b.state.clone())),
+                SortColumn::DlSpeed => a.dlspeed.cmp(&b.dlspeed),
+                SortColumn::UpSpeed => a.upspeed.cmp(&b.upspeed),
+                SortColumn::Eta => a.eta.cmp(&b.eta),
+                SortColumn::Ratio => a.ratio.partial_cmp(&b.ratio).unwrap_or(Ordering::Equal),
+            };
+            if self.cfg.sort_reverse { ordering.reverse() } else { ordering }
+        });
+        self.torrents = torrents;
+    }
+
+    /// Re-sort the torrents keeping the selection and persist the sort to the config.
+    pub fn apply_sort(&mut self) -> Option<Message> {
+        // Keep the config editor input in sync so saving it doesn't revert the sort.
+        self.input.sort_column = self.cfg.sort_column;
+        self.input.sort_reverse = self.cfg.sort_reverse;
+        self.rebuild_torrents();
+        match confy::store("qbtui", None, &self.cfg) {
+            Ok(_) => {},
+            Err(err) => self.notify(NotificationLevel::Error, format!("Failed to save sort to config file: {}", err)),
+        }
+        None
+    }
+
+    /// Toggle or cycle the highlighted field in the add torrent options form.
+    /// Categories are picked from the server's list in alphabetical order, starting with none.
+    pub fn change_add_option(&mut self, delta: isize) {
+        let options = &mut self.add_options;
+        match self.add_option_field {
+            AddOptionField::Category => {
+                let mut categories: Vec<&String> = self.torrent_sync.categories().keys().collect();
+                categories.sort();
+                let current = categories.iter().position(|c| **c == options.category).map_or(0, |i| i + 1);
+                let next = (current as isize + delta).rem_euclid(categories.len() as isize + 1) as usize;
+                options.category = match next {
+                    0 => String::new(),
+                    i => categories[i - 1].clone(),
+                };
+            },
+            AddOptionField::Paused => options.paused = !options.paused,
+            AddOptionField::SkipChecking => options.skip_checking = !options.skip_checking,
+            AddOptionField::ContentLayout => options.content_layout.cycle(),
+            AddOptionField::SequentialDownload => options.sequential_download = !options.sequential_download,
+            AddOptionField::FirstLastPiecePriority => {
+                options.first_last_piece_priority = !options.first_last_piece_priority;
+            },
+            _ => {}
+        }
+    }
+
+    /// Save the add torrent options as the defaults in the config.
+    /// The new name is specific to a torrent so it isn't saved.
+    pub fn save_add_torrent_defaults(&mut self) {
+        let mut defaults = self.add_options.clone();
+        defaults.rename.clear();
+        self.cfg.add_torrent_defaults = defaults.clone();
+        // Keep the config editor input in sync so saving it doesn't revert the defaults.
+        self.input.add_torrent_defaults = defaults;
+        match confy::store("qbtui", None, &self.cfg) {
+            Ok(_) => self.notify(NotificationLevel::Success, "Saved add torrent defaults"),
+            Err(err) => self.notify(NotificationLevel::Error, format!("Failed to save add torrent defaults: {}", err)),
+        }
+    }
+
+    /// Returns the hash of the currently selected torrent.
+    pub fn selected_hash(&self) -> Option<String> {
+        self.state.selected()
+            .and_then(|i| self.torrents.get(i))
+            .and_then(|torrent| torrent.hash.clone())
+    }
+
+    /// Returns the hashes of the torrents targeted by torrent actions.
+    /// This is the multi-selection when it is not empty, otherwise the highlighted torrent.
+    pub fn selected_hashes(&self) -> Vec<String> {
+        if self.selection.is_empty() {
+            return self.selected_hash().into_iter().collect();
+        }
+        self.torrents.iter()
+            .filter_map(|torrent| torrent.hash.clone())
+            .filter(|hash| self.selection.contains(hash))
+            .collect()
+    }
+
+    /// Add or remove the highlighted torrent from the multi-selection.
+    pub fn toggle_selection(&mut self) {
+        if let Some(hash) = self.selected_hash() {
+            if !self.selection.remove(&hash) {
+                self.selection.insert(hash);
+            }
+            // Toggling a single torrent ends the current range.
+            self.range_anchor = None;
+        }
+    }
+
+    /// Start a range selection from the highlighted torrent, or end the current one.
+    /// While a range is active every torrent between the anchor and the highlighted torrent is selected.
+    pub fn toggle_range_selection(&mut self) {
+        if self.range_anchor.is_some() {
+            self.range_anchor = None;
+            return;
+        }
+        if let Some(hash) = self.selected_hash() {
+            self.range_base = self.selection.clone();
+            self.range_anchor = Some(hash);
+            self.update_range_selection();
+        }
+    }
+
+    /// Select every torrent, or clear the selection if every torrent is already selected.
+    pub fn toggle_select_all(&mut self) {
+        self.range_anchor = None;
+        if !self.torrents.is_empty() && self.selection.len() == self.torrents.len() {
+            self.selection.clear();
+        } else {
+            self.selection = self.torrents.iter().filter_map(|torrent| torrent.hash.clone()).collect();
+        }
+    }
+
+    /// Returns true if the files tab is shown with files to act on.
+    pub fn files_focused(&self) -> bool {
+        self.torrent_popup && self.info_tab == SelectedInfoTab::Files && !self.file_tree.is_empty()
+    }
+
+    /// Rebuild the file tree from the torrent contents.
+    pub fn update_file_tree(&mut self) {
+        let paths: Vec<&str> = self.torrent_content.iter().map(|file| file.name.as_str()).collect();
+        self.file_tree = tree_rows(&paths);
+        self.file_rows = visible_rows(&self.file_tree, &self.collapsed_folders);
+        if let Some(i) = self.info_tab_state.selected()
+            && self.info_tab == SelectedInfoTab::Files
+            && i >= self.file_rows.len() {
+            self.info_tab_state.select(self.file_rows.len().checked_sub(1));
+        }
+    }
+
+    /// Returns the position in the file tree of the highlighted row in the files tab.
+    pub fn highlighted_tree_row(&self) -> Option<usize> {
+        self.info_tab_state.selected().and_then(|i| self.file_rows.get(i)).copied()
+    }
+
+    /// Returns the indexes of the files under the highlighted row, every file in it for a folder.
+    fn highlighted_files(&self) -> Vec<u64> {
+        self.highlighted_tree_row()
+            .map(|row| self.file_tree[row].files.iter().map(|i| self.torrent_content[*i].index).collect())
+            .unwrap_or_default()
+    }
+
+    /// Add or remove the highlighted file or folder from the multi-selection of files.
+    /// A partly selected folder is selected entirely.
+    pub fn toggle_file_selection(&mut self) {
+        let files = self.highlighted_files();
+        if files.iter().all(|index| self.file_selection.contains(index)) {
+            for index in files.iter() {
+                self.file_selection.remove(index);
+            }
+        } else {
+            self.file_selection.extend(files);
+        }
+    }
+
+    /// Returns the indexes of the files targeted by priority changes.
+    /// This is the multi-selection of files when it is not empty, otherwise the highlighted file or folder.
+    pub fn selected_file_indexes(&self) -> Vec<i64> {
+        let mut indexes: Vec<i64> = if self.file_selection.is_empty() {
+            self.highlighted_files().into_iter().map(|index| index as i64).collect()
+        } else {
+            self.file_selection.iter().map(|index| *index as i64).collect()
+        };
+        indexes.sort();
+        indexes
+    }
+
+    /// Returns what the rename prompt renames and its current name.
+    /// This is the highlighted file or folder in the files tab, otherwise the highlighted torrent.
+    pub fn rename_target(&self) -> Option<(RenameTarget, String)> {
+        if self.files_focused()
+            && let Some(row) = self.highlighted_tree_row() {
+            let row = &self.file_tree[row];
+            let hash = self.torrent_content_hash.clone();
+            let path = row.path.clone();
+            let target = if row.is_folder() {
+                RenameTarget::Folder { hash, path }
+            } else {
+                RenameTarget::File { hash, path }
+            };
+            return Some((target, row.label.clone()));
+        }
+        let torrent = self.state.selected().and_then(|i| self.torrents.get(i))?;
+        let hash = torrent.hash.clone()?;
+        Some((RenameTarget::Torrent { hash }, torrent.name.clone().unwrap_or_default()))
+    }
+
+    /// Returns the categories of the server sorted by name.
+    pub fn sorted_categories(&self) -> Vec<&Category> {
+        let mut categories: Vec<&Category> = self.torrent_sync.categories().values().collect();
+        categories.sort_by(|a, b| a.name.cmp(&b.name));
+        categories
+    }
+
+    /// Returns the highlighted category in the categories manager.
+    pub fn highlighted_category(&self) -> Option<&Category> {
+        self.categories_state.selected().and_then(|i| self.sorted_categories().get(i).copied())
+    }
+
+    /// Open the categories manager form to add a category or edit the save path of the highlighted one.
+    pub fn open_category_form(&mut self, form: CategoryForm) {
+        match form {
+            CategoryForm::Add => {
+                self.category_name_input.clear();
+                self.category_path_input.clear();
+                self.category_field = CategoryField::Name;
+            },
+            CategoryForm::Edit => {
+                let Some(category) = self.highlighted_category() else {
+                    return;
+                };
+                let (name, save_path) = (category.name.clone(), category.save_path.to_string_lossy().to_string());
+                self.category_name_input = name;
+                self.category_path_input = save_path;
+                self.category_field = CategoryField::SavePath;
+            },
+        }
+        self.category_form = Some(form);
+        self.reset_cursor();
+    }
+
+    /// Returns the category the picker would set, an empty string for no category.
+    /// The first entry of the picker removes the category.
+    pub fn picked_category(&self) -> Option<String> {
+        match self.category_picker_state.selected()? {
+            0 => Some(String::new()),
+            i => self.sorted_categories().get(i - 1).map(|category| category.name.clone()),
+        }
+    }
+
+    /// Collapse or expand the highlighted folder, a file collapses the folder it is in.
+    pub fn toggle_folder(&mut self) {
+        let Some(row) = self.highlighted_tree_row() else {
+            return;
+        };
+        let folder = if self.file_tree[row].is_folder() {
+            Some(row)
+        } else {
+            // The parent is the closest folder above the file with a lower depth.
+            let depth = self.file_tree[row].depth;
+            self.file_tree[..row].iter().rposition(|parent| parent.depth < depth)
+        };
+        let Some(folder) = folder else {
+            return;
+        };
+        let path = self.file_tree[folder].path.clone();
+        if !self.collapsed_folders.remove(&path) {
+            self.collapsed_folders.insert(path);
+        }
+        self.update_visible_files(folder);
+    }
+
+    /// Expand every folder, or collapse them all.
+    pub fn expand_all_folders(&mut self, expand: bool) {
+        let Some(row) = self.highlighted_tree_row() else {
+            return;
+        };
+        if expand {
+            self.collapsed_folders.clear();
+        } else {
+            self.collapsed_folders = self.file_tree.iter()
+                .filter(|row| row.is_folder())
+                .map(|row| row.path.clone())
+                .collect();
+        }
+        self.update_visible_files(row);
+    }
+
+    /// Recompute the visible rows after folders were collapsed or expanded.
+    /// The highlight moves to the given tree row, or the folder it was collapsed into.
+    fn update_visible_files(&mut self, highlight: usize) {
+        self.file_rows = visible_rows(&self.file_tree, &self.collapsed_folders);
+        // Rows between a collapsed folder and the highlight are hidden, so the last visible row before it is that folder.
+        if let Some(i) = self.file_rows.iter().rposition(|row| *row <= highlight) {
+            self.select_info_tab_row(i);
+        }
+    }
+
+    /// Recompute the selection from the range anchor to the highlighted torrent.
+    fn update_range_selection(&mut self) {
+        let Some(anchor) = self.range_anchor.as_ref() else {
+            return;
+        };
+        let anchor = self.torrents.iter().position(|torrent| torrent.hash.as_ref() == Some(anchor));
+        let (Some(anchor), Some(cursor)) = (anchor, self.state.selected()) else {
+            // The anchor torrent was removed.
+            self.range_anchor = None;
+            return;
+        };
+        let (start, end) = if anchor <= cursor { (anchor, cursor) } else { (cursor, anchor) };
+        self.selection = self.range_base.clone();
+        self.selection.extend(self.torrents[start..=end].iter().filter_map(|torrent| torrent.hash.clone()));
+    }
+
+    /// Drop torrents that no longer exist from the multi-selection.
+    /// Torrents hidden by the search or the sidebar filter stay selected.
+    fn prune_selection(&mut self) {
+        let sync = &self.torrent_sync;
+        self.selection.retain(|hash| sync.contains(hash));
+        self.update_range_selection();
+    }
+
+    /// Keep the selection on the torrent with the given hash after the torrent list changed.
+    /// Falls back to clamping the selected index when the torrent is no longer in the list.
+    pub fn restore_selection(&mut self, hash: Option<String>) {
+        let position = hash.and_then(|hash| {
+            self.torrents.iter().position(|torrent| torrent.hash.as_ref() == Some(&hash))
+        });
+        let i = match (position, self.state.selected()) {
+            (Some(i), _) => Some(i),
+            (None, _) if self.torrents.is_empty() => None,
+            (None, Some(i)) => Some(i.min(self.torrents.len() - 1)),
+            (None, None) => None,
+        };
+        self.state.select(i);
+        self.scroll_state = self.scroll_state.position(i.unwrap_or(0));
+        self.prune_selection();
+    }
+
+    /// Queue a notification to be shown as a toast and kept in the history.
+    pub fn notify(&mut self, level: NotificationLevel, message: impl Into<String>) {
+        self.notifications.push(level, message);
+    }
+
+    /// Returns true when the torrents are due for a background refresh.
+    /// The refresh is paused while a popup is open for editing and after a failed login,
+    /// so the server doesn't ban the client for repeating the wrong password, or extend a ban.
+    pub fn refresh_due(&self) -> bool {
+        if self.cfg.refresh_interval == 0
+            || matches!(self.connection, ConnectionState::AuthFailed | ConnectionState::Banned)
+            || !matches!(self.input_mode, InputMode::Normal | InputMode::Search | InputMode::Sidebar | InputMode::Notifications) {
+            return false;
+        }
+        self.next_refresh_in().is_some_and(|wait| wait.is_zero())
+    }
+
+    /// Time left until the next background refresh, None if background refreshes are disabled.
+    /// The interval doubles for each failed refresh.
+    pub fn next_refresh_in(&self) -> Option<Duration> {
+        if self.cfg.refresh_interval == 0 {
+            return None;
+        }
+        let backoff = 2u64.pow(self.refresh_failures.min(6));
+        let mut interval = self.cfg.refresh_interval
+            .saturating_mul(backoff)
+            .min(MAX_REFRESH_BACKOFF.max(self.cfg.refresh_interval));
+        let moving = self.torrents.iter().any(|torrent| torrent.state == Some(qbit_rs::model::State::Moving));
+        if moving && self.refresh_failures == 0 {
+            interval = interval.min(MOVING_REFRESH_INTERVAL);
+        }
+        match self.last_refresh {
+            Some(last) => Some(Duration::from_secs(interval).saturating_sub(last.elapsed())),
+            None => Some(Duration::ZERO),
+        }
+    }
+
     /// Convert unix timestamp to human readable string.
     pub fn timestamp_human_readable(&self, timestamp: Option<i64>) -> String {
         match timestamp  {
@@ -205,6 +918,53 @@ impl App {
         }
     }
 
+    /// Convert a rate limit in bytes/s to the short form accepted by [`parse_rate`].
+    /// No limit is shown as an empty string.
+    pub fn format_rate_input(&self, rate: i64) -> String {
+        if rate <= 0 {
+            return String::new();
+        }
+        let units = ["", "K", "M", "G", "T"];
+        let mut rate = rate as f64;
+        let mut unit = 0;
+        while rate >= 1024.0 && unit < units.len() - 1 {
+            rate /= 1024.0;
+            unit += 1;
+        }
+        // Drop trailing zeros so whole numbers show as "5M" instead of "5.00M".
+        let number = format!("{:.2}", rate);
+        let number = number.trim_end_matches('0').trim_end_matches('.');
+        format!("{}{}", number, units[unit])
+    }
+
+    /// Convert a per torrent rate limit to a human readable string, 0 or less is no limit.
+    pub fn format_rate_limit(&self, limit: Option<i64>) -> String {
+        match limit {
+            Some(limit) if limit > 0 => self.format_rate(limit),
+            _ => "∞".to_string(),
+        }
+    }
+
+    /// Convert a torrent ratio limit to a human readable string.
+    /// qBittorrent uses -2 for the global limit and -1 for no limit.
+    pub fn format_ratio_limit(&self, limit: Option<f64>) -> String {
+        match limit {
+            Some(limit) if limit >= 0.0 => format!("{:.2}", limit),
+            Some(-1.0) => "∞".to_string(),
+            _ => "Global".to_string(),
+        }
+    }
+
+    /// Convert a torrent seeding time limit in minutes to a human readable string.
+    /// qBittorrent uses -2 for the global limit and -1 for no limit.
+    pub fn format_seeding_time_limit(&self, limit: Option<i64>) -> String {
+        match limit {
+            Some(limit) if limit >= 0 => self.format_seconds(limit * 60),
+            Some(-1) => "∞".to_string(),
+            _ => "Global".to_string(),
+        }
+    }
+
     /// Convert seconds elapsed to formated string.
     /// Format: 1W:2D:3H:4M:5S
     pub fn format_seconds(&self, mut seconds: i64) -> String {
@@ -1,7 +1,17 @@
-use crate::{App, Message, SelectedAddTorrentTab};
+use crate::{enums::{AddOptionField, CategoryField, CategoryForm, SpeedLimitField, TorrentLimitField}, worker::{ApiResponse, ApiWorker}, App, Message, SelectedAddTorrentTab};
 use color_eyre::Result;
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use futures::{FutureExt, StreamExt};
+use qbit_rs::model::Priority;
+
+/// Wait for the next response from the API worker.
+/// Never resolves if there is no worker.
+async fn next_response(worker: &mut Option<ApiWorker>) -> Option<ApiResponse> {
+    match worker {
+        Some(worker) => worker.recv().await,
+        None => std::future::pending().await,
+    }
+}
 
 /// Ensure that the cursor position is within the bounds of the input string.
 fn clamp_cursor(new_cursor_pos: usize, input: &str) -> usize {
@@ -14,9 +24,25 @@ pub enum InputMode {
     Normal,
     Config,
     AddTorrent,
+    DeleteTorrent,
+    Search,
+    Sidebar,
+    Notifications,
+    SpeedLimits,
+    TorrentLimits,
+    Rename,
+    Move,
+    Categories,
+    CategoryPicker,
 }
 
 impl InputMode {
+    /// Returns true if Esc leaves this mode instead of quitting the application.
+    /// Every popup and panel closes with Esc, so it only quits from the torrents table.
+    fn leaves_on_esc(&self) -> bool {
+        *self != InputMode::Normal
+    }
+
     pub fn toggle_config(&mut self) {
         match self {
             InputMode::Normal => *self = InputMode::Config,
@@ -32,6 +58,86 @@ impl InputMode {
             _ => {}
         }
     }
+
+    pub fn toggle_search(&mut self) {
+        match self {
+            InputMode::Normal => *self = InputMode::Search,
+            InputMode::Search => *self = InputMode::Normal,
+            _ => {}
+        }
+    }
+
+    pub fn toggle_sidebar(&mut self) {
+        match self {
+            InputMode::Normal => *self = InputMode::Sidebar,
+            InputMode::Sidebar => *self = InputMode::Normal,
+            _ => {}
+        }
+    }
+
+    pub fn toggle_notifications(&mut self) {
+        match self {
+            InputMode::Normal => *self = InputMode::Notifications,
+            InputMode::Notifications => *self = InputMode::Normal,
+            _ => {}
+        }
+    }
+
+    pub fn toggle_speed_limits(&mut self) {
+        match self {
+            InputMode::Normal => *self = InputMode::SpeedLimits,
+            InputMode::SpeedLimits => *self = InputMode::Normal,
+            _ => {}
+        }
+    }
+
+    pub fn toggle_torrent_limits(&mut self) {
+        match self {
+            InputMode::Normal => *self = InputMode::TorrentLimits,
+            InputMode::TorrentLimits => *self = InputMode::Normal,
+            _ => {}
+        }
+    }
+
+    pub fn toggle_rename(&mut self) {
+        match self {
+            InputMode::Normal => *self = InputMode::Rename,
+            InputMode::Rename => *self = InputMode::Normal,
+            _ => {}
+        }
+    }
+
+    pub fn toggle_move(&mut self) {
+        match self {
+            InputMode::Normal => *self = InputMode::Move,
+            InputMode::Move => *self = InputMode::Normal,
+            _ => {}
+        }
+    }
+
+    pub fn toggle_categories(&mut self) {
+        match self {
+            InputMode::Normal => *self = InputMode::Categories,
+            InputMode::Categories => *self = InputMode::Normal,
+            _ => {}
+        }
+    }
+
+    pub fn toggle_category_picker(&mut self) {
+        match self {
+            InputMode::Normal => *self = InputMode::CategoryPicker,
+            InputMode::CategoryPicker => *self = InputMode::Normal,
+            _ => {}
+        }
+    }
+
+    pub fn toggle_delete_torrent(&mut self) {
+        match self {
+            InputMode::Normal => *self = InputMode::DeleteTorrent,
+            InputMode::DeleteTorrent => *self = InputMode::Normal,
+            _ => {}
+        }
+    }
 }
 
 /// Stores the currently selected config field being edited.
@@ -89,14 +195,23 @@ impl App {
                         Event::Key(key)
                             if key.kind == KeyEventKind::Press
                                 => return Ok(self.on_key_event(key)),
+                        Event::Paste(text) => return Ok(self.on_paste(&text)),
                         Event::Mouse(_) => {},
                         Event::Resize(_, _) => {}
                         _ => {}
                     }
                 }
             },
+            response = next_response(&mut self.worker) => {
+                if let Some(response) = response {
+                    return Ok(Some(Message::Api(Box::new(response))));
+                }
+            },
             _ = tokio::time::sleep(tokio::time::Duration::from_millis(100)) => {
                 // Sleep for a short duration to avoid busy waiting.
+                if self.refresh_due() {
+                    return Ok(Some(Message::RefreshTorrents));
+                }
             }
         }
         Ok(None)
@@ -105,10 +220,13 @@ impl App {
     /// Handles the key events and updates the state of [`App`].
     fn on_key_event(&mut self, key: KeyEvent) -> Option<Message>{
         let mut msg: Option<Message> = None;
-        // Global keys
+        // Global keys, handled before the mode keys so single letter actions can't override them.
+        // Ctrl + Shift + c is reported as an uppercase C with both modifiers.
         match (key.modifiers, key.code) {
-            (KeyModifiers::CONTROL, KeyCode::Char('c') | KeyCode::Char('C')) 
-            | (_, KeyCode::Esc) => msg = Some(Message::Quit),
+            (modifiers, KeyCode::Char('c') | KeyCode::Char('C')) if modifiers.contains(KeyModifiers::CONTROL) => {
+                return Some(Message::Quit);
+            },
+            (_, KeyCode::Esc) if !self.input_mode.leaves_on_esc() => return Some(Message::Quit),
             _ => {}
         }
 
@@ -126,19 +244,64 @@ impl App {
                         msg = Some(Message::DisplayAddTorrent);
                     },
                     (_, KeyCode::Tab) => msg = Some(Message::DisplayTorrentInfo),
+                    (_, KeyCode::Char('m')) => msg = Some(Message::DisplayNotifications),
+                    // Torrent limits, before the alternative speed limits key which matches any modifier
+                    (KeyModifiers::CONTROL, KeyCode::Char('t')) => msg = Some(Message::DisplayTorrentLimits),
+                    // Global speed limits
+                    (_, KeyCode::Char('t')) => msg = Some(Message::ToggleAltSpeedLimits),
+                    (KeyModifiers::CONTROL, KeyCode::Char('l')) => msg = Some(Message::DisplaySpeedLimits),
+                    (_, KeyCode::Char('R')) => msg = Some(Message::DisplayRename),
+                    (_, KeyCode::Char('M')) => msg = Some(Message::DisplayMove),
+                    // Categories
+                    (_, KeyCode::Char('g')) => msg = Some(Message::DisplayCategoryPicker),
+                    (_, KeyCode::Char('G')) => msg = Some(Message::DisplayCategories),
+                    (_, KeyCode::Char('C')) => msg = Some(Message::ToggleCategoryColumn),
+                    // Filter sidebar
+                    (_, KeyCode::Char('b')) => msg = Some(Message::DisplaySidebar),
+                    (_, KeyCode::BackTab) => msg = Some(Message::FocusSidebar),
+                    // Searching
+                    (_, KeyCode::Char('/')) => msg = Some(Message::DisplaySearch),
+                    (_, KeyCode::Char('n')) => msg = self.next_match(),
+                    (_, KeyCode::Char('N')) => msg = self.previous_match(),
+                    // Sorting
+                    (_, KeyCode::Char('o')) => msg = Some(Message::CycleSortColumn),
+                    (_, KeyCode::Char('O')) => msg = Some(Message::ToggleSortDirection),
+                    // Multi-selection
+                    (_, KeyCode::Char(' ')) if self.files_focused() => self.toggle_file_selection(),
+                    (_, KeyCode::Char(' ')) => self.toggle_selection(),
+                    (_, KeyCode::Char('v')) => self.toggle_range_selection(),
+                    (_, KeyCode::Char('A')) => self.toggle_select_all(),
+                    // File priorities in the files tab
+                    (_, KeyCode::Char('0')) if self.files_focused() => msg = Some(Message::SetFilePriority(Priority::DoNotDownload)),
+                    (_, KeyCode::Char('1')) if self.files_focused() => msg = Some(Message::SetFilePriority(Priority::Normal)),
+                    (_, KeyCode::Char('2')) if self.files_focused() => msg = Some(Message::SetFilePriority(Priority::High)),
+                    (_, KeyCode::Char('3')) if self.files_focused() => msg = Some(Message::SetFilePriority(Priority::Maximal)),
+                    // Folding the file tree
+                    (_, KeyCode::Enter) if self.files_focused() => self.toggle_folder(),
+                    (_, KeyCode::Char('+') | KeyCode::Char('=')) if self.files_focused() => self.expand_all_folders(true),
+                    (_, KeyCode::Char('-')) if self.files_focused() => self.expand_all_folders(false),
+                    // Torrent actions
+                    (_, KeyCode::Char('p')) => msg = Some(Message::PauseTorrents),
+                    (_, KeyCode::Char('s')) => msg = Some(Message::ResumeTorrents),
+                    (_, KeyCode::Char('f')) => msg = Some(Message::ForceStartTorrents),
+                    (_, KeyCode::Char('c')) => msg = Some(Message::RecheckTorrents),
+                    (_, KeyCode::Char('a')) => msg = Some(Message::ReannounceTorrents),
+                    (_, KeyCode::Char('d') | KeyCode::Delete) => msg = Some(Message::DisplayDeleteTorrent),
                     // Moving about the table
                     (_, KeyCode::Char('j') | KeyCode::Down) => msg = self.next_row(),
                     (_, KeyCode::Char('k') | KeyCode::Up) => msg = self.previous_row(),
                     (_, KeyCode::Char('h') | KeyCode::Left) => msg = self.previous_column(),
                     (_, KeyCode::Char('l') | KeyCode::Right) => msg = self.next_column(),
-                    // Delete input char
-                    (_, KeyCode::Backspace) => self.delete_char(),            
+                    (_, KeyCode::PageDown) => msg = self.scroll_page(1),
+                    (_, KeyCode::PageUp) => msg = self.scroll_page(-1),
+                    (_, KeyCode::Home) => msg = self.scroll_to(0),
+                    (_, KeyCode::End) => msg = self.scroll_to(usize::MAX),
                     _ => {}
                 }
             },
             InputMode::Config => {
                 match (key.modifiers, key.code) {
-                    (KeyModifiers::CONTROL, KeyCode::Char('e')) => {
+                    (KeyModifiers::CONTROL, KeyCode::Char('e')) | (_, KeyCode::Esc) => {
                         self.input = self.cfg.clone();
                         msg = Some(Message::DisplayCfgEditor);
                     },
@@ -158,19 +321,48 @@ impl App {
                 // Unsure if the nested matchs is the best way to handle this.
                 // But it currently works for now ¯\_(ツ)_/¯.
                 match (key.modifiers, key.code) {
-                    (KeyModifiers::CONTROL, KeyCode::Char('a')) => {
+                    (KeyModifiers::CONTROL, KeyCode::Char('a')) | (_, KeyCode::Esc) => {
                         msg = Some(Message::DisplayAddTorrent);
                     },
-                    (KeyModifiers::CONTROL, KeyCode::Char('w')) => {
-                        self.magnet_link.clear();
+                    (KeyModifiers::CONTROL, KeyCode::Char('o')) => {
+                        self.add_options_focus = !self.add_options_focus;
                         self.reset_cursor();
                     },
-                    (_, KeyCode::Tab) => self.add_torrent_tab.toggle(),                        
+                    (KeyModifiers::CONTROL, KeyCode::Char('s')) if self.add_options_focus => {
+                        self.save_add_torrent_defaults();
+                    },
+                    (KeyModifiers::CONTROL, KeyCode::Char('w')) 
+                        if !self.add_options_focus || self.add_option_field.is_text() => {
+                        self.current_input_mut().clear();
+                        self.reset_cursor();
+                    },
+                    (_, KeyCode::Tab) => self.add_torrent_tab.toggle(),
+                    _ if self.add_options_focus => {
+                        let text = self.add_option_field.is_text();
+                        match key.code {
+                            KeyCode::Enter => msg = self.submit_add_torrent(),
+                            KeyCode::Down => {
+                                self.add_option_field.shift(1);
+                                self.reset_cursor();
+                            },
+                            KeyCode::Up => {
+                                self.add_option_field.shift(-1);
+                                self.reset_cursor();
+                            },
+                            KeyCode::Char(to_insert) if text => self.enter_char(to_insert),
+                            KeyCode::Backspace if text => self.delete_char(),
+                            KeyCode::Left if text => msg = self.previous_column(),
+                            KeyCode::Right if text => msg = self.next_column(),
+                            KeyCode::Char(' ') | KeyCode::Right => self.change_add_option(1),
+                            KeyCode::Left => self.change_add_option(-1),
+                            _ => {}
+                        }
+                    },
                     _ => {
                         match self.add_torrent_tab {
                             SelectedAddTorrentTab::MagnetLink => {
                                 match key.code {
-                                    KeyCode::Enter => msg = Some(Message::AddTorrentMagnet),
+                                    KeyCode::Enter => msg = self.submit_add_torrent(),
                                     KeyCode::Char(to_insert) => self.enter_char(to_insert),
                                     KeyCode::Backspace => self.delete_char(), 
                                     // TODO: Add Delete key support.
@@ -179,13 +371,22 @@ impl App {
                                     _ => {}
                                 }
                             },
+                            SelectedAddTorrentTab::File if self.torrent_preview.is_some() => {
+                                let preview = self.torrent_preview.as_mut().unwrap();
+                                match key.code {
+                                    KeyCode::Enter => msg = self.submit_add_torrent(),
+                                    KeyCode::Char('j') | KeyCode::Down => preview.move_selection(1),
+                                    KeyCode::Char('k') | KeyCode::Up => preview.move_selection(-1),
+                                    KeyCode::Char(' ') => preview.toggle_selected(),
+                                    KeyCode::Backspace => self.torrent_preview = None,
+                                    _ => {}
+                                }
+                            },
                             SelectedAddTorrentTab::File => {
                                 if key.code == KeyCode::Enter {
-                                    msg = Some(Message::AddTorrentFile);
-                                    let index = self.file_explorer.as_ref().unwrap().selected_idx();
-                                    let files = self.file_explorer.as_ref().unwrap().files();
-                                    let path= files.get(index).unwrap().path();
-                                    self.torrent_file_path = path.to_string_lossy().to_string();
+                                    msg = self.submit_add_torrent();
+                                } else if key.code == KeyCode::Char(' ') {
+                                    self.toggle_marked_torrent_file();
                                 } else {
                                     let event = Event::Key(key);
                                     let _ =  self.file_explorer.as_mut().unwrap().handle(&event);
@@ -195,10 +396,264 @@ impl App {
                     }
                 }
             }
+            InputMode::Search => {
+                match (key.modifiers, key.code) {
+                    (_, KeyCode::Enter | KeyCode::Esc) => msg = Some(Message::DisplaySearch),
+                    (KeyModifiers::CONTROL, KeyCode::Char('w')) => {
+                        self.search_query.clear();
+                        self.reset_cursor();
+                        msg = Some(Message::FilterTorrents);
+                    },
+                    (KeyModifiers::CONTROL, KeyCode::Char('r')) => {
+                        self.search_regex = !self.search_regex;
+                        msg = Some(Message::FilterTorrents);
+                    },
+                    (_, KeyCode::Char(to_insert)) => {
+                        self.enter_char(to_insert);
+                        msg = Some(Message::FilterTorrents);
+                    },
+                    (_, KeyCode::Backspace) => {
+                        self.delete_char();
+                        msg = Some(Message::FilterTorrents);
+                    },
+                    (_, KeyCode::Left) => msg = self.previous_column(),
+                    (_, KeyCode::Right) => msg = self.next_column(),
+                    (_, KeyCode::Down) => msg = self.scroll_down(),
+                    (_, KeyCode::Up) => msg = self.scroll_up(),
+                    _ => {}
+                }
+            },
+            InputMode::Sidebar => {
+                match (key.modifiers, key.code) {
+                    (_, KeyCode::Char('b') | KeyCode::Esc) => msg = Some(Message::DisplaySidebar),
+                    (_, KeyCode::BackTab) => msg = Some(Message::FocusSidebar),
+                    (_, KeyCode::Char('r')) => msg = Some(Message::RefreshTorrents),
+                    (_, KeyCode::Char('j') | KeyCode::Down) => self.sidebar_move(1),
+                    (_, KeyCode::Char('k') | KeyCode::Up) => self.sidebar_move(-1),
+                    (_, KeyCode::Enter | KeyCode::Char(' ')) => self.apply_sidebar_entry(),
+                    _ => {}
+                }
+            },
+            InputMode::SpeedLimits => {
+                match (key.modifiers, key.code) {
+                    (KeyModifiers::CONTROL, KeyCode::Char('l')) | (_, KeyCode::Esc) => msg = Some(Message::DisplaySpeedLimits),
+                    (_, KeyCode::Enter) => msg = Some(Message::SetSpeedLimits),
+                    (KeyModifiers::CONTROL, KeyCode::Char('w')) => {
+                        self.current_input_mut().clear();
+                        self.reset_cursor();
+                    },
+                    (_, KeyCode::Char(to_insert)) => self.enter_char(to_insert),
+                    (_, KeyCode::Backspace) => self.delete_char(),
+                    (_, KeyCode::Tab | KeyCode::Down) => msg = self.next_row(),
+                    (_, KeyCode::BackTab | KeyCode::Up) => msg = self.previous_row(),
+                    (_, KeyCode::Left) => msg = self.previous_column(),
+                    (_, KeyCode::Right) => msg = self.next_column(),
+                    _ => {}
+                }
+            },
+            InputMode::TorrentLimits => {
+                match (key.modifiers, key.code) {
+                    (KeyModifiers::CONTROL, KeyCode::Char('t')) | (_, KeyCode::Esc) => msg = Some(Message::DisplayTorrentLimits),
+                    (_, KeyCode::Enter) => msg = Some(Message::SetTorrentLimits),
+                    (KeyModifiers::CONTROL, KeyCode::Char('w')) => {
+                        self.current_input_mut().clear();
+                        self.reset_cursor();
+                    },
+                    (_, KeyCode::Char(to_insert)) => self.enter_char(to_insert),
+                    (_, KeyCode::Backspace) => self.delete_char(),
+                    (_, KeyCode::Tab | KeyCode::Down) => msg = self.next_row(),
+                    (_, KeyCode::BackTab | KeyCode::Up) => msg = self.previous_row(),
+                    (_, KeyCode::Left) => msg = self.previous_column(),
+                    (_, KeyCode::Right) => msg = self.next_column(),
+                    _ => {}
+                }
+            },
+            InputMode::Rename => {
+                match (key.modifiers, key.code) {
+                    (KeyModifiers::CONTROL, KeyCode::Char('r')) | (_, KeyCode::Esc) => msg = Some(Message::DisplayRename),
+                    (_, KeyCode::Enter) => msg = Some(Message::Rename),
+                    (KeyModifiers::CONTROL, KeyCode::Char('w')) => {
+                        self.rename_input.clear();
+                        self.reset_cursor();
+                    },
+                    (_, KeyCode::Char(to_insert)) => self.enter_char(to_insert),
+                    (_, KeyCode::Backspace) => self.delete_char(),
+                    (_, KeyCode::Left) => msg = self.previous_column(),
+                    (_, KeyCode::Right) => msg = self.next_column(),
+                    (_, KeyCode::Home) => self.charcter_index = 0,
+                    (_, KeyCode::End) => self.reset_cursor(),
+                    _ => {}
+                }
+            },
+            InputMode::Move => {
+                match (key.modifiers, key.code) {
+                    (KeyModifiers::CONTROL, KeyCode::Char('g')) | (_, KeyCode::Esc) => msg = Some(Message::DisplayMove),
+                    (_, KeyCode::Tab) => {
+                        self.move_browse = !self.move_browse;
+                        self.reset_cursor();
+                    },
+                    // The path follows the directory opened in the file explorer.
+                    _ if self.move_browse => {
+                        if let Some(explorer) = self.file_explorer.as_mut()
+                            && explorer.handle(&Event::Key(key)).is_ok() {
+                            self.move_input = explorer.cwd().to_string_lossy().to_string();
+                        }
+                    },
+                    (_, KeyCode::Enter) => msg = Some(Message::MoveTorrents),
+                    (KeyModifiers::CONTROL, KeyCode::Char('w')) => {
+                        self.move_input.clear();
+                        self.reset_cursor();
+                    },
+                    (_, KeyCode::Char(to_insert)) => self.enter_char(to_insert),
+                    (_, KeyCode::Backspace) => self.delete_char(),
+                    (_, KeyCode::Left) => msg = self.previous_column(),
+                    (_, KeyCode::Right) => msg = self.next_column(),
+                    (_, KeyCode::Home) => self.charcter_index = 0,
+                    (_, KeyCode::End) => self.reset_cursor(),
+                    _ => {}
+                }
+            },
+            InputMode::Categories if self.category_form.is_some() => {
+                match (key.modifiers, key.code) {
+                    (KeyModifiers::CONTROL, KeyCode::Char('g')) | (_, KeyCode::Esc) => self.category_form = None,
+                    (_, KeyCode::Enter) => msg = Some(Message::SaveCategory),
+                    (KeyModifiers::CONTROL, KeyCode::Char('w')) => {
+                        self.current_input_mut().clear();
+                        self.reset_cursor();
+                    },
+                    // Existing categories can't be renamed.
+                    (_, KeyCode::Tab | KeyCode::BackTab | KeyCode::Down | KeyCode::Up)
+                        if self.category_form == Some(CategoryForm::Add) => {
+                        self.category_field.toggle();
+                        self.reset_cursor();
+                    },
+                    (_, KeyCode::Char(to_insert)) => self.enter_char(to_insert),
+                    (_, KeyCode::Backspace) => self.delete_char(),
+                    (_, KeyCode::Left) => msg = self.previous_column(),
+                    (_, KeyCode::Right) => msg = self.next_column(),
+                    _ => {}
+                }
+            },
+            InputMode::Categories => {
+                // Any other key cancels a pending removal.
+                let pending = self.category_remove_pending.take();
+                match (key.modifiers, key.code) {
+                    (_, KeyCode::Char('G') | KeyCode::Esc) => msg = Some(Message::DisplayCategories),
+                    (_, KeyCode::Char('j') | KeyCode::Down) => self.categories_state.select_next(),
+                    (_, KeyCode::Char('k') | KeyCode::Up) => self.categories_state.select_previous(),
+                    (_, KeyCode::Char('a')) => self.open_category_form(CategoryForm::Add),
+                    (_, KeyCode::Char('e') | KeyCode::Enter) => self.open_category_form(CategoryForm::Edit),
+                    (_, KeyCode::Char('d') | KeyCode::Delete) => {
+                        let highlighted = self.highlighted_category().map(|category| category.name.clone());
+                        if pending.is_some() && pending == highlighted {
+                            msg = Some(Message::RemoveCategory);
+                        }
+                        self.category_remove_pending = highlighted;
+                    },
+                    _ => {}
+                }
+            },
+            InputMode::CategoryPicker => {
+                match (key.modifiers, key.code) {
+                    (_, KeyCode::Char('g') | KeyCode::Esc) => msg = Some(Message::DisplayCategoryPicker),
+                    (_, KeyCode::Char('j') | KeyCode::Down) => self.category_picker_state.select_next(),
+                    (_, KeyCode::Char('k') | KeyCode::Up) => self.category_picker_state.select_previous(),
+                    (_, KeyCode::Enter) => msg = Some(Message::SetCategory),
+                    _ => {}
+                }
+            },
+            InputMode::Notifications => {
+                match (key.modifiers, key.code) {
+                    (_, KeyCode::Char('m') | KeyCode::Esc) => msg = Some(Message::DisplayNotifications),
+                    (_, KeyCode::Char('j') | KeyCode::Down) => {
+                        let last = self.notifications.len().saturating_sub(1) as u16;
+                        self.notifications_scroll = self.notifications_scroll.saturating_add(1).min(last);
+                    },
+                    (_, KeyCode::Char('k') | KeyCode::Up) => {
+                        self.notifications_scroll = self.notifications_scroll.saturating_sub(1);
+                    },
+                    _ => {}
+                }
+            },
+            InputMode::DeleteTorrent => {
+                match (key.modifiers, key.code) {
+                    (_, KeyCode::Char('y') | KeyCode::Enter) => msg = Some(Message::DeleteTorrents),
+                    (_, KeyCode::Char('n') | KeyCode::Char('d') | KeyCode::Delete | KeyCode::Esc) => {
+                        msg = Some(Message::DisplayDeleteTorrent);
+                    },
+                    (_, KeyCode::Tab | KeyCode::Char(' ')) => self.delete_files = !self.delete_files,
+                    _ => {}
+                }
+            }
         }
         msg
     }
 
+    /// Inserts pasted text at the cursor of the current input field.
+    /// Newlines are kept in the magnet link to paste several links at once and dropped elsewhere.
+    fn on_paste(&mut self, text: &str) -> Option<Message> {
+        let keep_newlines = self.input_mode == InputMode::AddTorrent
+            && !self.add_options_focus
+            && self.add_torrent_tab == SelectedAddTorrentTab::MagnetLink;
+        let typed = match self.input_mode {
+            InputMode::Config | InputMode::Search | InputMode::SpeedLimits | InputMode::TorrentLimits | InputMode::Rename => true,
+            InputMode::Move => !self.move_browse,
+            InputMode::Categories => self.category_form.is_some(),
+            InputMode::AddTorrent => keep_newlines || (self.add_options_focus && self.add_option_field.is_text()),
+            _ => false,
+        };
+        if !typed {
+            return None;
+        }
+        for c in text.chars().filter(|c| *c != '\r') {
+            match c {
+                '\n' if keep_newlines => self.enter_char('\n'),
+                '\n' => {},
+                c => self.enter_char(c),
+            }
+        }
+        if self.input_mode == InputMode::Search {
+            return Some(Message::FilterTorrents);
+        }
+        None
+    }
+
+    /// Mark or unmark the file under the cursor in the file explorer to add it with the other marked files.
+    fn toggle_marked_torrent_file(&mut self) {
+        let Some(explorer) = self.file_explorer.as_ref() else {
+            return;
+        };
+        let file = explorer.current();
+        if file.is_dir() {
+            return;
+        }
+        let path = file.path().clone();
+        match self.marked_torrent_files.iter().position(|marked| *marked == path) {
+            Some(i) => {
+                self.marked_torrent_files.remove(i);
+            },
+            None => self.marked_torrent_files.push(path),
+        }
+    }
+
+    /// Add the magnet link or the torrent files, depending on the selected tab.
+    fn submit_add_torrent(&mut self) -> Option<Message> {
+        match self.add_torrent_tab {
+            SelectedAddTorrentTab::MagnetLink => Some(Message::AddTorrentMagnet),
+            // Marked files are added together, a single file is previewed first.
+            SelectedAddTorrentTab::File if self.torrent_preview.is_some() || !self.marked_torrent_files.is_empty() => {
+                Some(Message::AddTorrentFile)
+            },
+            SelectedAddTorrentTab::File => {
+                let index = self.file_explorer.as_ref().unwrap().selected_idx();
+                let files = self.file_explorer.as_ref().unwrap().files();
+                let path= files.get(index).unwrap().path();
+                self.torrent_file_path = path.to_string_lossy().to_string();
+                Some(Message::PreviewTorrentFile)
+            }
+        }
+    }
+
     /// Move the selection down in the InputMode context.
     /// In Normal mode, it moves down the torrent table.
     /// In Config mode, it moves down the config inputs.
@@ -212,6 +667,14 @@ impl App {
                 let input = self.current_input();
                 self.charcter_index = clamp_cursor(input.len(), input);
             },
+            InputMode::SpeedLimits => {
+                self.speed_limit_field.toggle();
+                self.reset_cursor();
+            },
+            InputMode::TorrentLimits => {
+                self.torrent_limit_field.shift(1);
+                self.reset_cursor();
+            },
             _ => {}
         }
         None
@@ -230,6 +693,14 @@ impl App {
                 let input = self.current_input();
                 self.charcter_index = clamp_cursor(input.len(), input);
             },
+            InputMode::SpeedLimits => {
+                self.speed_limit_field.toggle();
+                self.reset_cursor();
+            },
+            InputMode::TorrentLimits => {
+                self.torrent_limit_field.shift(-1);
+                self.reset_cursor();
+            },
             _ => {}
         }
         None
@@ -249,11 +720,12 @@ impl App {
                 let cursor_moved_right = self.charcter_index.saturating_add(1);
                 self.charcter_index = clamp_cursor(cursor_moved_right, input); 
             },
-            InputMode::AddTorrent => {
+            InputMode::AddTorrent | InputMode::Search | InputMode::SpeedLimits | InputMode::TorrentLimits | InputMode::Rename | InputMode::Move | InputMode::Categories => {
                 let input = self.current_input();
                 let cursor_moved_right = self.charcter_index.saturating_add(1);
                 self.charcter_index = clamp_cursor(cursor_moved_right, input);
             }
+            InputMode::DeleteTorrent | InputMode::Sidebar | InputMode::Notifications | InputMode::CategoryPicker => {}
         }
         None
     }
@@ -272,11 +744,12 @@ impl App {
                 let cursor_moved_left = self.charcter_index.saturating_sub(1);
                 self.charcter_index = clamp_cursor(cursor_moved_left, input);
             },
-            InputMode::AddTorrent => {
+            InputMode::AddTorrent | InputMode::Search | InputMode::SpeedLimits | InputMode::TorrentLimits | InputMode::Rename | InputMode::Move | InputMode::Categories => {
                 let input = self.current_input();
                 let cursor_moved_left = self.charcter_index.saturating_sub(1);
                 self.charcter_index = clamp_cursor(cursor_moved_left, input);
             }
+            InputMode::DeleteTorrent | InputMode::Sidebar | InputMode::Notifications | InputMode::CategoryPicker => {}
         }
         None
     }
@@ -291,9 +764,49 @@ impl App {
                     CurentInput::Password => &self.input.password
                 }
             },
+            InputMode::AddTorrent if self.add_options_focus => {
+                // Only text fields are typed in, the others never move the cursor.
+                match self.add_option_field {
+                    AddOptionField::SavePath => &self.add_options.savepath,
+                    AddOptionField::Tags => &self.add_options.tags,
+                    AddOptionField::Rename => &self.add_options.rename,
+                    AddOptionField::DownloadLimit => &self.add_options.download_limit,
+                    AddOptionField::UploadLimit => &self.add_options.upload_limit,
+                    _ => &self.magnet_link,
+                }
+            },
             InputMode::AddTorrent => {
                 &self.magnet_link
             },
+            InputMode::Search => {
+                &self.search_query
+            },
+            InputMode::SpeedLimits => {
+                match self.speed_limit_field {
+                    SpeedLimitField::Download => &self.download_limit_input,
+                    SpeedLimitField::Upload => &self.upload_limit_input,
+                }
+            },
+            InputMode::TorrentLimits => {
+                match self.torrent_limit_field {
+                    TorrentLimitField::Download => &self.torrent_dl_limit_input,
+                    TorrentLimitField::Upload => &self.torrent_up_limit_input,
+                    TorrentLimitField::Ratio => &self.ratio_limit_input,
+                    TorrentLimitField::SeedingTime => &self.seeding_time_limit_input,
+                }
+            },
+            InputMode::Rename => {
+                &self.rename_input
+            },
+            InputMode::Move => {
+                &self.move_input
+            },
+            InputMode::Categories => {
+                match self.category_field {
+                    CategoryField::Name => &self.category_name_input,
+                    CategoryField::SavePath => &self.category_path_input,
+                }
+            },
             _ => panic!("Cannot access input in other modes"),
         }
     }
@@ -308,9 +821,48 @@ impl App {
                     CurentInput::Password => &mut self.input.password
                 }
             },
+            InputMode::AddTorrent if self.add_options_focus => {
+                match self.add_option_field {
+                    AddOptionField::SavePath => &mut self.add_options.savepath,
+                    AddOptionField::Tags => &mut self.add_options.tags,
+                    AddOptionField::Rename => &mut self.add_options.rename,
+                    AddOptionField::DownloadLimit => &mut self.add_options.download_limit,
+                    AddOptionField::UploadLimit => &mut self.add_options.upload_limit,
+                    _ => &mut self.magnet_link,
+                }
+            },
             InputMode::AddTorrent => {
                 &mut self.magnet_link
             },
+            InputMode::Search => {
+                &mut self.search_query
+            },
+            InputMode::SpeedLimits => {
+                match self.speed_limit_field {
+                    SpeedLimitField::Download => &mut self.download_limit_input,
+                    SpeedLimitField::Upload => &mut self.upload_limit_input,
+                }
+            },
+            InputMode::TorrentLimits => {
+                match self.torrent_limit_field {
+                    TorrentLimitField::Download => &mut self.torrent_dl_limit_input,
+                    TorrentLimitField::Upload => &mut self.torrent_up_limit_input,
+                    TorrentLimitField::Ratio => &mut self.ratio_limit_input,
+                    TorrentLimitField::SeedingTime => &mut self.seeding_time_limit_input,
+                }
+            },
+            InputMode::Rename => {
+                &mut self.rename_input
+            },
+            InputMode::Move => {
+                &mut self.move_input
+            },
+            InputMode::Categories => {
+                match self.category_field {
+                    CategoryField::Name => &mut self.category_name_input,
+                    CategoryField::SavePath => &mut self.category_path_input,
+                }
+            },
             _ => panic!("Cannot access input in other modes"),
         }
     }
@@ -345,11 +897,14 @@ impl App {
         }
     }
 
-    /// Resets the charcter index cursor to the end of the current input field.
+    /// Resets the charcter index cursor to the end of the current input field,
+    /// or to the start in modes without one so it isn't left past a closed input.
     pub fn reset_cursor(&mut self) {
         // Needs to ensure that self.current_input() is not called on an InputMode that results in a panic.
-        if self.input_mode != InputMode::Normal {
+        if matches!(self.input_mode, InputMode::Config | InputMode::AddTorrent | InputMode::Search | InputMode::SpeedLimits | InputMode::TorrentLimits | InputMode::Rename | InputMode::Move | InputMode::Categories) {
             self.charcter_index = self.current_input().chars().count();
+        } else {
+            self.charcter_index = 0;
         }
     }
 }
\ No newline at end of file
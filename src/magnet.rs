@@ -0,0 +1,199 @@
+use color_eyre::{eyre::eyre, Result};
+
+/// Fields of a magnet link used by BitTorrent clients.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Magnet {
+    /// Hex v1 info-hash from an urn:btih topic.
+    pub info_hash: Option<String>,
+    /// Hex v2 info-hash from an urn:btmh topic, without the multihash prefix.
+    pub info_hash_v2: Option<String>,
+    /// Display name.
+    pub name: Option<String>,
+    pub trackers: Vec<String>,
+    /// Exact length of the content in bytes.
+    pub length: Option<u64>,
+}
+
+impl Magnet {
+    /// Parse a magnet link, which needs at least one btih or btmh topic.
+    pub fn parse(link: &str) -> Result<Self> {
+        let query = link.get(..8)
+            .filter(|scheme| scheme.eq_ignore_ascii_case("magnet:?"))
+            .map(|_| &link[8..])
+            .ok_or_else(|| eyre!("not a magnet link"))?;
+        let mut magnet = Magnet::default();
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').ok_or_else(|| eyre!("parameter {} has no value", pair))?;
+            let value = percent_decode(value)?;
+            // Parameters can be numbered such as tr.1 and tr.2.
+            match key.split('.').next().unwrap_or(key) {
+                "xt" => magnet.parse_topic(&value)?,
+                "dn" => magnet.name = Some(value),
+                "tr" => magnet.trackers.push(value),
+                "xl" => {
+                    let length = value.parse().map_err(|_| eyre!("invalid length {}", value))?;
+                    magnet.length = Some(length);
+                },
+                _ => {},
+            }
+        }
+        if magnet.info_hash.is_none() && magnet.info_hash_v2.is_none() {
+            return Err(eyre!("missing the info-hash (xt=urn:btih or urn:btmh)"));
+        }
+        Ok(magnet)
+    }
+
+    /// Parse an exact topic, which is a v1 or v2 info-hash.
+    /// Topics of other networks such as urn:ed2k are ignored.
+    fn parse_topic(&mut self, topic: &str) -> Result<()> {
+        let lower = topic.to_ascii_lowercase();
+        if let Some(hash) = lower.strip_prefix("urn:btih:") {
+            let hash = match hash.len() {
+                40 if hash.chars().all(|c| c.is_ascii_hexdigit()) => hash.to_string(),
+                32 => base32_to_hex(hash).ok_or_else(|| eyre!("invalid base32 info-hash"))?,
+                _ => return Err(eyre!("invalid info-hash, expected 40 hex or 32 base32 characters")),
+            };
+            self.info_hash = Some(hash);
+        } else if let Some(hash) = lower.strip_prefix("urn:btmh:") {
+            // A SHA-256 multihash starts with the code 0x12 and the length 0x20.
+            match hash.strip_prefix("1220") {
+                Some(digest) if digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit()) => {
+                    self.info_hash_v2 = Some(digest.to_string());
+                },
+                _ => return Err(eyre!("invalid v2 info-hash, expected a SHA-256 multihash")),
+            }
+        }
+        Ok(())
+    }
+
+    /// The hash qBittorrent identifies the torrent by.
+    /// Torrents with only a v2 info-hash use it truncated to the length of a v1 hash.
+    pub fn torrent_id(&self) -> Option<String> {
+        self.info_hash.clone()
+            .or_else(|| self.info_hash_v2.as_ref().map(|hash| hash[..40].to_string()))
+    }
+}
+
+/// Check a link entered to add a torrent.
+/// Returns the parsed magnet link, None for other URLs or an error for malformed links.
+pub fn check_link(link: &str) -> Result<Option<Magnet>> {
+    let (scheme, rest) = link.split_once(':').unwrap_or_default();
+    match scheme.to_ascii_lowercase().as_str() {
+        "magnet" => Magnet::parse(link).map(Some),
+        "http" | "https" if rest.strip_prefix("//").is_some_and(|host| !host.is_empty()) => Ok(None),
+        _ => Err(eyre!("expected a magnet link or an http(s) URL")),
+    }
+}
+
+/// Decode %XX escapes and + as a space.
+fn percent_decode(value: &str) -> Result<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = value.get(i + 1..i + 3).ok_or_else(|| eyre!("invalid escape in {}", value))?;
+                let byte = u8::from_str_radix(hex, 16).map_err(|_| eyre!("invalid escape in {}", value))?;
+                decoded.push(byte);
+                i += 3;
+            },
+            b'+' => {
+                decoded.push(b' ');
+                i += 1;
+            },
+            byte => {
+                decoded.push(byte);
+                i += 1;
+            },
+        }
+    }
+    Ok(String::from_utf8_lossy(&decoded).to_string())
+}
+
+/// Convert a base32 encoded info-hash to hex.
+fn base32_to_hex(hash: &str) -> Option<String> {
+    const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz234567";
+    let mut bits: u64 = 0;
+    let mut bit_count = 0;
+    let mut hex = String::with_capacity(40);
+    for c in hash.bytes() {
+        let value = ALPHABET.iter().position(|a| *a == c)? as u64;
+        bits = (bits << 5) | value;
+        bit_count += 5;
+        while bit_count >= 4 {
+            bit_count -= 4;
+            hex.push_str(&format!("{:x}", (bits >> bit_count) & 0xf));
+        }
+    }
+    Some(hex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEX: &str = "c12fe1c06bba254a9dc9f519b335aa7c1367a88a";
+    const V2: &str = "d8dd32ac93357c368556af3ac1d95c9d76bd0dff6fa9833ecdac3d53134efabb";
+
+    #[test]
+    fn hex_btih() {
+        let magnet = Magnet::parse(&format!("magnet:?xt=urn:btih:{}&dn=Some+name&tr=udp%3A%2F%2Ftracker&xl=42", HEX.to_uppercase())).unwrap();
+        assert_eq!(magnet.info_hash.as_deref(), Some(HEX));
+        assert_eq!(magnet.name.as_deref(), Some("Some name"));
+        assert_eq!(magnet.trackers, vec!["udp://tracker"]);
+        assert_eq!(magnet.length, Some(42));
+        assert_eq!(magnet.torrent_id().as_deref(), Some(HEX));
+    }
+
+    #[test]
+    fn base32_btih() {
+        let magnet = Magnet::parse("magnet:?xt=urn:btih:YEX6DQDLXISUVHOJ6UM3GNNKPQJWPKEK").unwrap();
+        assert_eq!(magnet.info_hash.as_deref(), Some(HEX));
+    }
+
+    #[test]
+    fn btmh() {
+        let magnet = Magnet::parse(&format!("magnet:?xt=urn:btmh:1220{}", V2)).unwrap();
+        assert_eq!(magnet.info_hash, None);
+        assert_eq!(magnet.info_hash_v2.as_deref(), Some(V2));
+        assert_eq!(magnet.torrent_id().as_deref(), Some(&V2[..40]));
+    }
+
+    #[test]
+    fn several_topics() {
+        let link = format!("magnet:?xt.1=urn:ed2k:31d6cfe0d16ae931b73c59d7e0c089c0&xt.2=urn:btih:{}&xt.3=urn:btmh:1220{}", HEX, V2);
+        let magnet = Magnet::parse(&link).unwrap();
+        assert_eq!(magnet.info_hash.as_deref(), Some(HEX));
+        assert_eq!(magnet.info_hash_v2.as_deref(), Some(V2));
+        // Hybrid torrents are identified by their v1 hash.
+        assert_eq!(magnet.torrent_id().as_deref(), Some(HEX));
+    }
+
+    #[test]
+    fn malformed() {
+        for link in [
+            "magnet:",
+            "magnet:?dn=name",
+            "magnet:?xt=urn:ed2k:31d6cfe0d16ae931b73c59d7e0c089c0",
+            "magnet:?xt=urn:btih:1234",
+            "magnet:?xt=urn:btih:YEX6DQDLXISUVHOJ6UM3GNNKPQJWPKE1",
+            "magnet:?xt=urn:btmh:1114aaaa",
+            "magnet:?xt",
+            "magnet:?xt=urn:btih:%zz",
+            &format!("magnet:?xt=urn:btih:{}&xl=big", HEX),
+            &format!("http:?xt=urn:btih:{}", HEX),
+        ] {
+            assert!(Magnet::parse(link).is_err(), "{} should be rejected", link);
+        }
+    }
+
+    #[test]
+    fn links() {
+        assert!(check_link(&format!("MAGNET:?xt=urn:btih:{}", HEX)).unwrap().is_some());
+        assert!(check_link("https://example.com/file.torrent").unwrap().is_none());
+        assert!(check_link("https://").is_err());
+        assert!(check_link("ftp://example.com/file.torrent").is_err());
+        assert!(check_link("file.torrent").is_err());
+    }
+}
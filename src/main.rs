@@ -1,13 +1,15 @@
 use color_eyre::Result;
-use crossterm::event::EventStream;
+use crossterm::event::{DisableBracketedPaste, EnableBracketedPaste, EventStream};
 use ratatui::{
     layout::{Constraint, Layout},  
-    widgets::{TableState, ScrollbarState}, 
+    widgets::{ListState, TableState, ScrollbarState}, 
     DefaultTerminal, Frame
 };
 use ratatui_explorer::{FileExplorer, Theme};
 use qbit_rs::model::Tracker;
+use regex::Regex;
 use serde::{Serialize, Deserialize};
+use std::{collections::HashSet, path::PathBuf, time::Instant};
 // Local imports
 mod input;
 use input::{CurentInput, InputMode};
@@ -17,13 +19,54 @@ mod api;
 mod signals;
 use signals::Message;
 mod enums;
-use enums::{SelectedInfoTab, ScrollContext, SelectedAddTorrentTab};
+mod sync;
+use sync::TorrentSync;
+mod worker;
+use worker::ApiWorker;
+mod notifications;
+use notifications::Notifications;
+mod bencode;
+mod metainfo;
+mod magnet;
+mod file_tree;
+use file_tree::TreeRow;
+use metainfo::TorrentPreview;
+use enums::{AddOptionField, CategoryField, CategoryForm, ContentLayout, ConnectionState, RenameTarget, SelectedInfoTab, ScrollContext, SelectedAddTorrentTab, SidebarEntry, SortColumn, SpeedLimitField, StatusFilter, TorrentLimitField};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
 struct AppConfig {
     api_url: String,
     username: String,
     password: String,
+    /// Seconds between background refreshes of the torrent list, 0 disables it.
+    refresh_interval: u64,
+    /// Column the torrents table is sorted by, None keeps the order torrents were added.
+    sort_column: Option<SortColumn>,
+    sort_reverse: bool,
+    /// Show the category of each torrent in the torrents table.
+    show_category_column: bool,
+    /// Options the add torrent form starts with.
+    add_torrent_defaults: AddTorrentOptions,
+}
+
+/// Options for adding torrents set in the add torrent form.
+/// Rate limits are kept as entered, like "5M", and parsed when the torrent is added.
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[serde(default)]
+struct AddTorrentOptions {
+    savepath: String,
+    category: String,
+    /// Comma separated tags.
+    tags: String,
+    rename: String,
+    download_limit: String,
+    upload_limit: String,
+    paused: bool,
+    skip_checking: bool,
+    content_layout: ContentLayout,
+    sequential_download: bool,
+    first_last_piece_priority: bool,
 }
 
 impl ::std::default::Default for AppConfig {
@@ -32,16 +75,33 @@ impl ::std::default::Default for AppConfig {
             api_url: "http://localhost:8080".into(),
             username: "admin".into(),
             password: "".into(),
+            refresh_interval: 5,
+            sort_column: None,
+            sort_reverse: false,
+            show_category_column: false,
+            add_torrent_defaults: AddTorrentOptions::default(),
         }
     }
 }
 
+impl AppConfig {
+    /// Returns true if both configs connect to the same server with the same credentials.
+    fn same_server(&self, other: &AppConfig) -> bool {
+        self.api_url == other.api_url
+            && self.username == other.username
+            && self.password == other.password
+    }
+}
+
 #[tokio::main]
 async fn main() -> color_eyre::Result<()> {
     //let cfg: AppConfig = confy::load("qbtui", None)?;
     color_eyre::install()?;
     let terminal = ratatui::init();
+    // Bracketed paste sends pasted text as one event so newlines in it don't submit inputs.
+    crossterm::execute!(std::io::stdout(), EnableBracketedPaste)?;
     let result = App::new().run(terminal).await;
+    crossterm::execute!(std::io::stdout(), DisableBracketedPaste)?;
     ratatui::restore();
     result
 }
@@ -52,6 +112,14 @@ pub struct App {
     event_stream: EventStream,
     state: TableState,
     scroll_state: ScrollbarState,
+    // First torrent shown and number of torrents that fit in the table.
+    torrents_offset: usize,
+    torrents_viewport: usize,
+    // Multi-selection of torrent hashes used by bulk actions.
+    selection: HashSet<String>,
+    // Range selection start and the selection from before the range started.
+    range_anchor: Option<String>,
+    range_base: HashSet<String>,
     info_tab_state: TableState,
     info_tab_scroll_state: ScrollbarState,
     scroll_context: ScrollContext,
@@ -68,9 +136,27 @@ pub struct App {
     cfg: AppConfig,
     // Torrent data storage
     torrents: Vec<qbit_rs::model::Torrent>,
+    torrent_sync: TorrentSync,
     torrent_trackers: Vec<Tracker>,
     torrent_peers: Option<qbit_rs::model::PeerSyncData>,
+    torrent_peers_hash: String,
     torrent_content: Vec<qbit_rs::model::TorrentContent>,
+    torrent_content_hash: String,
+    // Torrent contents as a directory tree, the rows outside collapsed folders and the collapsed folder paths.
+    file_tree: Vec<TreeRow>,
+    file_rows: Vec<usize>,
+    collapsed_folders: HashSet<String>,
+    // Multi-selection of file indexes in the files tab used by priority changes.
+    file_selection: HashSet<u64>,
+    // API worker and the number of requests waiting for a response.
+    worker: Option<ApiWorker>,
+    pending_requests: usize,
+    syncing: bool,
+    spinner_frame: usize,
+    // Connection to the server and background refresh
+    connection: ConnectionState,
+    last_refresh: Option<Instant>,
+    refresh_failures: u32,
     // Torrent info popup
     torrent_popup: bool, 
     info_tab: SelectedInfoTab,
@@ -80,6 +166,64 @@ pub struct App {
     magnet_link: String,
     file_explorer: Option<FileExplorer>,
     torrent_file_path: String,
+    // Torrent files marked in the file explorer to add together.
+    marked_torrent_files: Vec<PathBuf>,
+    // Preview of the torrent file picked in the file explorer.
+    torrent_preview: Option<TorrentPreview>,
+    add_options: AddTorrentOptions,
+    add_options_focus: bool,
+    add_option_field: AddOptionField,
+    // Delete torrent popup
+    delete_torrent_popup: bool,
+    delete_files: bool,
+    // Torrent name search
+    search_query: String,
+    search_regex: bool,
+    search_pattern: Option<Regex>,
+    // Filter sidebar
+    sidebar_popup: bool,
+    sidebar_state: ListState,
+    sidebar_entries: Vec<(SidebarEntry, usize)>,
+    status_filter: StatusFilter,
+    category_filter: Option<String>,
+    tag_filter: Option<String>,
+    tracker_filter: Option<String>,
+    // Global speed limits popup
+    speed_limits_popup: bool,
+    speed_limit_field: SpeedLimitField,
+    download_limit_input: String,
+    upload_limit_input: String,
+    // Torrent limits popup
+    torrent_limits_popup: bool,
+    torrent_limit_field: TorrentLimitField,
+    torrent_dl_limit_input: String,
+    torrent_up_limit_input: String,
+    ratio_limit_input: String,
+    seeding_time_limit_input: String,
+    // Move popup with the new save path, browsed with the file explorer when it has focus
+    move_popup: bool,
+    move_input: String,
+    move_browse: bool,
+    // Categories manager popup and its form for adding or editing a category
+    categories_popup: bool,
+    categories_state: ListState,
+    category_form: Option<CategoryForm>,
+    category_field: CategoryField,
+    category_name_input: String,
+    category_path_input: String,
+    // Category that is removed when the remove key is pressed again
+    category_remove_pending: Option<String>,
+    // Picker setting the category of the selected torrents
+    category_picker_popup: bool,
+    category_picker_state: ListState,
+    // Rename prompt for a torrent or a file or folder in the files tab
+    rename_popup: bool,
+    rename_target: Option<RenameTarget>,
+    rename_input: String,
+    // Notification toasts and history popup
+    notifications: Notifications,
+    notifications_popup: bool,
+    notifications_scroll: u16,
 }
 
 impl App {
@@ -95,11 +239,11 @@ impl App {
         self.cfg = confy::load("qbtui", None)?;
         self.input = self.cfg.clone();
         self.file_explorer = Some(FileExplorer::with_theme(Theme::default().add_default_title()).unwrap());
-        self.get_torrents().await?;
+        self.start_worker();
+        self.get_torrents();
         while self.running {
             terminal.draw(|frame| self.draw(frame))?;
             let mut msg = self.handle_crossterm_events().await?;
-            // TODO: With time delay regularly refresh the torrents.
             while msg.is_some() {
                 msg = self.update(msg.unwrap()).await;
             }
@@ -108,20 +252,34 @@ impl App {
     }
 
     fn draw(&mut self, frame: &mut Frame) {
-        let rects;
-        let footer: usize;
-        // Split frame area depending on whether the torrent info section is active.
+        // Split frame area depending on whether the torrent info section and search bar are active.
+        let show_search = self.input_mode == InputMode::Search || !self.search_query.is_empty();
+        let mut constraints = vec![Constraint::Min(5)];
         if self.torrent_popup {
-            let vertical = &Layout::vertical([Constraint::Min(5), Constraint::Length(17), Constraint::Length(4)]);
-            rects = vertical.split(frame.area());
-            footer = 2;
-        } else {
-            let vertical = &Layout::vertical([Constraint::Min(5), Constraint::Length(4)]);
-            rects = vertical.split(frame.area());
-            footer = 1;
+            constraints.push(Constraint::Length(18));
+        }
+        if show_search {
+            constraints.push(Constraint::Length(3));
         }
+        constraints.push(Constraint::Length(1));
+        constraints.push(Constraint::Length(5));
+        let rects = Layout::vertical(constraints).split(frame.area());
+        let footer = rects.len() - 1;
+        let status_bar = footer - 1;
 
-        self.render_torrents_table(frame, rects[0]);
+        // Show the filter sidebar to the left of the torrents table.
+        if self.sidebar_popup {
+            let horizontal = Layout::horizontal([Constraint::Length(32), Constraint::Min(20)]);
+            let columns = horizontal.split(rects[0]);
+            self.render_sidebar(frame, columns[0]);
+            self.render_torrents_table(frame, columns[1]);
+        } else {
+            self.render_torrents_table(frame, rects[0]);
+        }
+        if show_search {
+            self.render_search_bar(frame, rects[status_bar - 1]);
+        }
+        self.render_status_bar(frame, rects[status_bar]);
         self.render_footer(frame, rects[footer]);      
 
         // Show torrent info footer
@@ -147,9 +305,51 @@ impl App {
         }
         // Show add torrent popup on user input.
         if self.add_torrent_popup {
-            let area = self.popup_area(frame.area(), 70, 50);
+            let area = self.popup_area(frame.area(), 70, 80);
             self.render_add_torrent_popup(frame, area);
         }
+        // Show global speed limits popup on user input.
+        if self.speed_limits_popup {
+            let area = self.popup_area(frame.area(), 50, 30);
+            self.render_speed_limits_popup(frame, area);
+        }
+        // Show torrent limits popup on user input.
+        if self.torrent_limits_popup {
+            let area = self.popup_area(frame.area(), 50, 40);
+            self.render_torrent_limits_popup(frame, area);
+        }
+        // Show move torrents popup on user input.
+        if self.move_popup {
+            let area = self.popup_area(frame.area(), 70, 60);
+            self.render_move_popup(frame, area);
+        }
+        // Show categories manager on user input.
+        if self.categories_popup {
+            let area = self.popup_area(frame.area(), 60, 60);
+            self.render_categories_popup(frame, area);
+        }
+        // Show category picker on user input.
+        if self.category_picker_popup {
+            let area = self.popup_area(frame.area(), 40, 50);
+            self.render_category_picker(frame, area);
+        }
+        // Show rename prompt on user input.
+        if self.rename_popup {
+            let area = self.popup_area(frame.area(), 60, 30);
+            self.render_rename_popup(frame, area);
+        }
+        // Show notification history on user input.
+        if self.notifications_popup {
+            let area = self.popup_area(frame.area(), 70, 60);
+            self.render_notifications_popup(frame, area);
+        }
+        // Show delete torrent confirmation on user input.
+        if self.delete_torrent_popup {
+            let area = self.popup_area(frame.area(), 50, 30);
+            self.render_delete_torrent_popup(frame, area);
+        }
+        // Toasts are drawn over everything else.
+        self.render_toasts(frame, frame.area());
     }
 }
 
@@ -0,0 +1,293 @@
+use crate::{bencode::{self, Value}, file_tree::{tree_rows, TreeRow}};
+use color_eyre::{eyre::eyre, Result};
+use ratatui::widgets::ListState;
+use sha1::{Digest, Sha1};
+use sha2::Sha256;
+
+/// A file in a torrent.
+#[derive(Debug, Clone)]
+pub struct MetainfoFile {
+    /// Path of the file, starting with the torrent name for torrents with several files.
+    pub path: String,
+    pub length: i64,
+}
+
+/// Metadata read from a .torrent file.
+#[derive(Debug, Clone)]
+pub struct Metainfo {
+    pub name: String,
+    pub total_size: i64,
+    /// Files in the order qBittorrent numbers them, without padding files.
+    pub files: Vec<MetainfoFile>,
+    pub piece_length: i64,
+    pub trackers: Vec<String>,
+    pub private: bool,
+    /// Hex hash qBittorrent identifies the torrent by, the SHA-1 of the info dictionary,
+    /// or its SHA-256 cut to 40 characters for torrents that only have v2 metadata.
+    pub info_hash: String,
+}
+
+impl Metainfo {
+    /// Parse the contents of a .torrent file.
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let decoded = bencode::decode(data).map_err(|err| eyre!("not a torrent file ({})", err))?;
+        let info = decoded.value.get("info").ok_or_else(|| eyre!("missing the info dictionary"))?;
+        let info_range = decoded.info.filter(|_| info.as_dict().is_some())
+            .ok_or_else(|| eyre!("the info section is not a dictionary"))?;
+        let name = info.get("name.utf-8")
+            .or_else(|| info.get("name"))
+            .and_then(Value::as_string)
+            .ok_or_else(|| eyre!("missing the torrent name"))?;
+        let piece_length = info.get("piece length")
+            .and_then(Value::as_integer)
+            .filter(|length| *length > 0)
+            .ok_or_else(|| eyre!("missing the piece length"))?;
+
+        // Torrents that only have v2 metadata list their files in a tree and have no v1 file list.
+        let v2_only = info.get("files").is_none() && info.get("length").is_none();
+        let files = if let Some(files) = info.get("files") {
+            parse_files(&name, files)?
+        } else if let Some(length) = info.get("length").and_then(Value::as_integer) {
+            vec![MetainfoFile { path: name.clone(), length }]
+        } else if let Some(tree) = info.get("file tree") {
+            let mut files = vec![];
+            parse_file_tree(&name, tree, &mut files)?;
+            files
+        } else {
+            return Err(eyre!("missing the file list"));
+        };
+        if files.iter().any(|file| file.length < 0) {
+            return Err(eyre!("a file has a negative size"));
+        }
+
+        // Every tier of the announce list, falling back to the single announce URL.
+        let mut trackers: Vec<String> = vec![];
+        let tiers = decoded.value.get("announce-list").and_then(Value::as_list).unwrap_or_default();
+        let announce = decoded.value.get("announce").and_then(Value::as_string);
+        for tracker in tiers.iter()
+            .filter_map(Value::as_list)
+            .flatten()
+            .filter_map(Value::as_string)
+            .chain(announce) {
+            if !tracker.is_empty() && !trackers.contains(&tracker) {
+                trackers.push(tracker);
+            }
+        }
+
+        let info_data = &data[info_range];
+        let digest = if v2_only { Sha256::digest(info_data)[..20].to_vec() } else { Sha1::digest(info_data).to_vec() };
+        let info_hash = digest.iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        Ok(Self {
+            total_size: files.iter().map(|file| file.length).sum(),
+            name,
+            files,
+            piece_length,
+            trackers,
+            private: info.get("private").and_then(Value::as_integer) == Some(1),
+            info_hash,
+        })
+    }
+}
+
+/// Parse the v1 file list of a torrent with several files.
+fn parse_files(name: &str, files: &Value) -> Result<Vec<MetainfoFile>> {
+    let files = files.as_list().ok_or_else(|| eyre!("the file list is not a list"))?;
+    let mut parsed = vec![];
+    for file in files {
+        // Padding files align the other files to pieces and aren't shown by qBittorrent.
+        let attr = file.get("attr").and_then(Value::as_string).unwrap_or_default();
+        if attr.contains('p') {
+            continue;
+        }
+        let length = file.get("length")
+            .and_then(Value::as_integer)
+            .ok_or_else(|| eyre!("a file is missing its size"))?;
+        let path = file.get("path.utf-8")
+            .or_else(|| file.get("path"))
+            .and_then(Value::as_list)
+            .map(|parts| parts.iter().filter_map(Value::as_string).collect::<Vec<_>>())
+            .filter(|parts| !parts.is_empty())
+            .ok_or_else(|| eyre!("a file is missing its path"))?;
+        parsed.push(MetainfoFile { path: format!("{}/{}", name, path.join("/")), length });
+    }
+    if parsed.is_empty() {
+        return Err(eyre!("the torrent has no files"));
+    }
+    Ok(parsed)
+}
+
+/// Parse a v2 file tree, where each file is a dictionary under an empty key.
+fn parse_file_tree(path: &str, tree: &Value, files: &mut Vec<MetainfoFile>) -> Result<()> {
+    let tree = tree.as_dict().ok_or_else(|| eyre!("the file tree is not a dictionary"))?;
+    for (key, value) in tree {
+        if key.is_empty() {
+            let length = value.get("length")
+                .and_then(Value::as_integer)
+                .ok_or_else(|| eyre!("a file is missing its size"))?;
+            files.push(MetainfoFile { path: path.to_string(), length });
+        } else {
+            let child = format!("{}/{}", path, String::from_utf8_lossy(key));
+            parse_file_tree(&child, value, files)?;
+        }
+    }
+    Ok(())
+}
+
+/// Preview of a .torrent file picked in the add torrent popup.
+/// Unticked files are set to not download once the torrent is added.
+#[derive(Debug)]
+pub struct TorrentPreview {
+    pub path: String,
+    pub data: Vec<u8>,
+    pub metainfo: Metainfo,
+    pub wanted: Vec<bool>,
+    pub rows: Vec<TreeRow>,
+    pub state: ListState,
+}
+
+impl TorrentPreview {
+    pub fn new(path: String, data: Vec<u8>, metainfo: Metainfo) -> Self {
+        let paths: Vec<&str> = metainfo.files.iter().map(|file| file.path.as_str()).collect();
+        let rows = tree_rows(&paths);
+        Self {
+            path,
+            data,
+            wanted: vec![true; metainfo.files.len()],
+            metainfo,
+            rows,
+            state: ListState::default().with_selected(Some(0)),
+        }
+    }
+
+    /// Indexes of the unticked files.
+    pub fn unwanted(&self) -> Vec<i64> {
+        self.wanted.iter()
+            .enumerate()
+            .filter(|(_, wanted)| !**wanted)
+            .map(|(i, _)| i as i64)
+            .collect()
+    }
+
+    /// Move the highlighted row, staying within the tree.
+    pub fn move_selection(&mut self, delta: isize) {
+        let last = self.rows.len().saturating_sub(1);
+        let current = self.state.selected().unwrap_or(0);
+        self.state.select(Some(current.saturating_add_signed(delta).min(last)));
+    }
+
+    /// Tick or untick the highlighted file, or every file in the highlighted folder.
+    pub fn toggle_selected(&mut self) {
+        let Some(row) = self.state.selected().and_then(|i| self.rows.get(i)) else {
+            return;
+        };
+        // A partly ticked folder is ticked entirely.
+        let wanted = !row.files.iter().all(|i| self.wanted[*i]);
+        for i in row.files.iter() {
+            self.wanted[*i] = wanted;
+        }
+    }
+
+    /// Returns true if every file covered by the row is ticked, false if none are and None if some are.
+    pub fn row_wanted(&self, row: &TreeRow) -> Option<bool> {
+        let wanted = row.files.iter().filter(|i| self.wanted[**i]).count();
+        match wanted {
+            0 => Some(false),
+            n if n == row.files.len() => Some(true),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wrap an info dictionary in a torrent file.
+    fn torrent(info: &str) -> Vec<u8> {
+        format!("d8:announce9:udp://one13:announce-listll9:udp://twoel9:udp://oneee4:info{}e", info).into_bytes()
+    }
+
+    fn paths(metainfo: &Metainfo) -> Vec<(&str, i64)> {
+        metainfo.files.iter().map(|file| (file.path.as_str(), file.length)).collect()
+    }
+
+    #[test]
+    fn single_file() {
+        let metainfo = Metainfo::parse(&torrent("d6:lengthi10e4:name8:file.txt12:piece lengthi16384e7:privatei1ee")).unwrap();
+        assert_eq!(metainfo.name, "file.txt");
+        assert_eq!(paths(&metainfo), vec![("file.txt", 10)]);
+        assert_eq!(metainfo.total_size, 10);
+        assert_eq!(metainfo.piece_length, 16384);
+        assert_eq!(metainfo.trackers, vec!["udp://two", "udp://one"]);
+        assert!(metainfo.private);
+        assert_eq!(metainfo.info_hash, "574357eaac7bcd1c9ab7f0dbc79229217beb58d9");
+    }
+
+    #[test]
+    fn several_files() {
+        let info = "d5:filesld6:lengthi3e4:pathl1:a5:b.txteed4:attr1:p6:lengthi5e4:pathl4:.pad1:5eed6:lengthi4e4:pathl5:c.txteee4:name3:dir12:piece lengthi16384ee";
+        let metainfo = Metainfo::parse(&torrent(info)).unwrap();
+        // The padding file is left out.
+        assert_eq!(paths(&metainfo), vec![("dir/a/b.txt", 3), ("dir/c.txt", 4)]);
+        assert_eq!(metainfo.total_size, 7);
+        assert!(!metainfo.private);
+        assert_eq!(metainfo.info_hash, "bd0ec94f4a66a86c34d73c4a754d89fdf8f08ab1");
+    }
+
+    #[test]
+    fn v2_only() {
+        let info = "d9:file treed3:dird5:a.txtd0:d6:lengthi7eee5:b.txtd0:d6:lengthi2eeeee12:meta versioni2e4:name3:dir12:piece lengthi16384ee";
+        let metainfo = Metainfo::parse(&torrent(info)).unwrap();
+        assert_eq!(paths(&metainfo), vec![("dir/dir/a.txt", 7), ("dir/dir/b.txt", 2)]);
+        // qBittorrent identifies v2-only torrents by their SHA-256 info-hash cut to 40 characters.
+        assert_eq!(metainfo.info_hash, "f910dde20c0a8bafbf78e18fefd3f6c1a3b62a34");
+    }
+
+    #[test]
+    fn hybrid() {
+        let info = "d9:file treed5:a.txtd0:d6:lengthi3eeee5:filesld6:lengthi3e4:pathl5:a.txteee12:meta versioni2e4:name3:dir12:piece lengthi16384ee";
+        let metainfo = Metainfo::parse(&torrent(info)).unwrap();
+        assert_eq!(paths(&metainfo), vec![("dir/a.txt", 3)]);
+        assert_eq!(metainfo.info_hash, "a10e9e154fc9d13e101f76206db202252b0a7d98");
+    }
+
+    #[test]
+    fn invalid() {
+        for data in [
+            b"not bencode".to_vec(),
+            b"d8:announce3:urle".to_vec(),
+            torrent("le"),
+            torrent("d6:lengthi10e12:piece lengthi16384ee"),
+            torrent("d6:lengthi10e4:name1:xe"),
+            torrent("d4:name1:x12:piece lengthi16384ee"),
+            torrent("d6:lengthi-1e4:name1:x12:piece lengthi16384ee"),
+            torrent("d5:filesle4:name1:x12:piece lengthi16384ee"),
+            torrent("d5:filesld4:pathl1:aeee4:name1:x12:piece lengthi16384ee"),
+        ] {
+            assert!(Metainfo::parse(&data).is_err(), "{:?} should be rejected", String::from_utf8_lossy(&data));
+        }
+    }
+
+    #[test]
+    fn preview_selection() {
+        let info = "d5:filesld6:lengthi1e4:pathl1:a1:xeed6:lengthi1e4:pathl1:a1:yeed6:lengthi1e4:pathl1:zeee4:name3:dir12:piece lengthi16384ee";
+        let metainfo = Metainfo::parse(&torrent(info)).unwrap();
+        let mut preview = TorrentPreview::new("dir.torrent".to_string(), vec![], metainfo);
+        // Rows: dir, dir/a, dir/a/x, dir/a/y, dir/z.
+        preview.move_selection(2);
+        preview.toggle_selected();
+        assert_eq!(preview.unwanted(), vec![0]);
+        assert_eq!(preview.row_wanted(&preview.rows[1]), None);
+        // A partly ticked folder is ticked entirely.
+        preview.move_selection(-1);
+        preview.toggle_selected();
+        assert_eq!(preview.unwanted(), Vec::<i64>::new());
+        preview.toggle_selected();
+        assert_eq!(preview.unwanted(), vec![0, 1]);
+        assert_eq!(preview.row_wanted(&preview.rows[1]), Some(false));
+        preview.move_selection(10);
+        assert_eq!(preview.state.selected(), Some(4));
+    }
+}
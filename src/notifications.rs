@@ -0,0 +1,76 @@
+use std::time::{Duration, Instant};
+use crate::enums::NotificationLevel;
+
+/// How long a notification is shown as a toast.
+const TOAST_DURATION: Duration = Duration::from_secs(5);
+/// Maximum number of notifications kept in the history.
+const HISTORY_LENGTH: usize = 200;
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub level: NotificationLevel,
+    pub message: String,
+    pub created: Instant,
+    /// Wall clock time the notification was created, shown in the history.
+    pub timestamp: String,
+}
+
+/// Queue of notifications shown as timed toasts, with a history of past notifications.
+#[derive(Debug, Default)]
+pub struct Notifications {
+    history: Vec<Notification>,
+}
+
+impl Notifications {
+    /// Add a notification, dropping the oldest one once the history is full.
+    /// Repeating the newest notification while it is still shown restarts its toast instead.
+    pub fn push(&mut self, level: NotificationLevel, message: impl Into<String>) {
+        let message = message.into();
+        if let Some(last) = self.history.last_mut()
+            && last.level == level && last.message == message && last.created.elapsed() < TOAST_DURATION {
+            last.created = Instant::now();
+            return;
+        }
+        if self.history.len() >= HISTORY_LENGTH {
+            self.history.remove(0);
+        }
+        self.history.push(Notification {
+            level,
+            message,
+            created: Instant::now(),
+            timestamp: chrono::Local::now().format("%H:%M:%S").to_string(),
+        });
+    }
+
+    /// Notifications that are still shown as toasts, newest first.
+    pub fn active(&self) -> impl Iterator<Item = &Notification> {
+        self.history.iter().rev().take_while(|n| n.created.elapsed() < TOAST_DURATION)
+    }
+
+    /// Every notification in the history, newest first.
+    pub fn history(&self) -> impl Iterator<Item = &Notification> {
+        self.history.iter().rev()
+    }
+
+    pub fn len(&self) -> usize {
+        self.history.len()
+    }
+}
+
+/// Convert an error from the qBittorrent API into a readable message.
+pub fn describe_error(err: &qbit_rs::Error) -> String {
+    match err {
+        qbit_rs::Error::HttpError(err) if err.is_connect() => "Could not connect to the qBittorrent server".to_string(),
+        qbit_rs::Error::HttpError(err) if err.is_timeout() => "The qBittorrent server timed out".to_string(),
+        qbit_rs::Error::HttpError(err) => match err.status() {
+            Some(status) => format!("The qBittorrent server returned {}", status),
+            None => "Could not reach the qBittorrent server".to_string(),
+        },
+        // qBittorrent doesn't set a cookie when the login is rejected.
+        qbit_rs::Error::BadResponse { .. } => "Login failed, check the username and password".to_string(),
+        qbit_rs::Error::UnknownHttpCode(status) => format!("The qBittorrent server returned {}", status),
+        qbit_rs::Error::NonAsciiHeader => "The qBittorrent server sent an invalid response".to_string(),
+        qbit_rs::Error::SerdeJsonError(_) => "The qBittorrent server sent a response that couldn't be read".to_string(),
+        qbit_rs::Error::ApiError(err) => err.to_string(),
+    }
+}
@@ -1,4 +1,5 @@
-use crate::App;
+use qbit_rs::model::Priority;
+use crate::{enums::{AddOptionField, NotificationLevel, SpeedLimitField, TorrentLimitField}, worker::ApiResponse, App, InputMode, SortColumn};
 
 pub enum Message {
     /// Refresh the list of torrents and other displayed torrent data.
@@ -9,19 +10,95 @@ pub enum Message {
     TorrentTrackers,
     /// Api call to get/refresh the selected torrent peers.
     TorrentPeers,
+    /// Api call to pause the selected torrents.
+    PauseTorrents,
+    /// Api call to resume the selected torrents.
+    ResumeTorrents,
+    /// Api call to toggle force start on the selected torrents.
+    ForceStartTorrents,
+    /// Api call to recheck the selected torrents.
+    RecheckTorrents,
+    /// Api call to reannounce the selected torrents to their trackers.
+    ReannounceTorrents,
+    /// Toggle the display of the delete torrent confirmation popup.
+    /// Also toggles InputMode to/from DeleteTorrent.
+    DisplayDeleteTorrent,
+    /// Api call to delete the selected torrents, optionally with their data.
+    DeleteTorrents,
+    /// Toggle the display of the filter sidebar.
+    DisplaySidebar,
+    /// Move the focus between the filter sidebar and the torrents table.
+    /// Also toggles InputMode to/from Sidebar.
+    FocusSidebar,
+    /// Toggle the torrent name search bar.
+    /// Also toggles InputMode to/from Search.
+    DisplaySearch,
+    /// Filter the torrents table with the current search query.
+    FilterTorrents,
+    /// Cycle the column the torrents table is sorted by.
+    CycleSortColumn,
+    /// Toggle between ascending and descending sort.
+    ToggleSortDirection,
     /// Toggle the display of the torrent info popup.
     DisplayTorrentInfo,
     /// Toggle the display of the add torrent popup.
     DisplayAddTorrent,
+    /// Close the add torrent popup once a torrent is added, if it is still open.
+    CloseAddTorrent,
     /// Api call to add a torrent using a magnet link.
     AddTorrentMagnet,
-    /// Api call to add a torrent using a torrent file.
+    /// Parse the torrent file under the cursor and show its preview.
+    PreviewTorrentFile,
+    /// Api call to add the previewed or marked torrent files.
     AddTorrentFile,
     /// Toggle the display of the configuration editor popup.
     /// Also toggles InputMode to/from Config.
     DisplayCfgEditor,
     /// Save the current configuration to disk.
     SaveCfg,
+    /// Toggle the display of the torrent limits popup for the selected torrents.
+    /// Also toggles InputMode to/from TorrentLimits.
+    DisplayTorrentLimits,
+    /// Api call to set the rate and share limits of the selected torrents.
+    SetTorrentLimits,
+    /// Api call to switch between the normal and alternative global speed limits.
+    ToggleAltSpeedLimits,
+    /// Toggle the display of the global speed limits popup.
+    /// Also toggles InputMode to/from SpeedLimits.
+    DisplaySpeedLimits,
+    /// Api call to set the global speed limits from the speed limits popup.
+    SetSpeedLimits,
+    /// Api call to set the priority of the selected files in the files tab.
+    SetFilePriority(Priority),
+    /// Toggle the display of the move popup for the selected torrents.
+    /// Also toggles InputMode to/from Move.
+    DisplayMove,
+    /// Api call to move the selected torrents to the path in the move popup.
+    MoveTorrents,
+    /// Toggle the display of the categories manager popup.
+    /// Also toggles InputMode to/from Categories.
+    DisplayCategories,
+    /// Api call to add or edit the category in the categories manager form.
+    SaveCategory,
+    /// Api call to remove the highlighted category in the categories manager.
+    RemoveCategory,
+    /// Toggle the display of the category picker for the selected torrents.
+    /// Also toggles InputMode to/from CategoryPicker.
+    DisplayCategoryPicker,
+    /// Api call to set the picked category on the selected torrents.
+    SetCategory,
+    /// Show or hide the category column of the torrents table.
+    ToggleCategoryColumn,
+    /// Toggle the rename prompt for the highlighted torrent, or the highlighted file or folder in the files tab.
+    /// Also toggles InputMode to/from Rename.
+    DisplayRename,
+    /// Api call to rename the target of the rename prompt.
+    Rename,
+    /// Toggle the display of the notification history popup.
+    /// Also toggles InputMode to/from Notifications.
+    DisplayNotifications,
+    /// Response from the API worker.
+    Api(Box<ApiResponse>),
     /// Quit and exit the application.
     Quit,
 }
@@ -31,41 +108,117 @@ impl App {
     pub async fn update(&mut self, msg: Message) -> Option<Message> {
         match msg {
             Message::RefreshTorrents => {
-                let _ = self.get_torrents().await;
+                self.get_torrents();
                 // Chain messages to refresh other displayed data.
                 if self.torrent_popup {
                     return self.info_tab.update_selected();
                 }
             }
             Message::TorrentFiles => {
-                let _ = self.get_torrent_contents().await;
+                self.get_torrent_contents();
             }
             Message::TorrentTrackers => {
-                let _ = self.get_torrent_trackers().await;
+                self.get_torrent_trackers();
             }
             Message::TorrentPeers => {
-                let _ = self.get_torrent_peers().await;
+                self.get_torrent_peers();
+            }
+            Message::PauseTorrents => {
+                self.pause_torrents();
+            }
+            Message::ResumeTorrents => {
+                self.resume_torrents();
+            }
+            Message::ForceStartTorrents => {
+                self.toggle_force_start();
+            }
+            Message::RecheckTorrents => {
+                self.recheck_torrents();
+            }
+            Message::ReannounceTorrents => {
+                self.reannounce_torrents();
+            }
+            Message::DisplayDeleteTorrent => {
+                // Nothing to delete, so don't open the popup.
+                if !self.delete_torrent_popup && self.selected_hashes().is_empty() {
+                    return None;
+                }
+                self.delete_torrent_popup = !self.delete_torrent_popup;
+                self.delete_files = false;
+                self.input_mode.toggle_delete_torrent();
+                return Some(Message::RefreshTorrents);
+            }
+            Message::DeleteTorrents => {
+                self.delete_torrents();
+                return Some(Message::DisplayDeleteTorrent);
+            }
+            Message::DisplaySidebar => {
+                self.sidebar_popup = !self.sidebar_popup;
+                // Hiding the sidebar moves the focus back to the table.
+                if !self.sidebar_popup && self.input_mode == InputMode::Sidebar {
+                    self.input_mode.toggle_sidebar();
+                }
+                self.rebuild_torrents();
+            }
+            Message::FocusSidebar => {
+                if !self.sidebar_popup {
+                    self.sidebar_popup = true;
+                    self.rebuild_torrents();
+                }
+                self.input_mode.toggle_sidebar();
+            }
+            Message::DisplaySearch => {
+                self.input_mode.toggle_search();
+                self.reset_cursor();
+            }
+            Message::FilterTorrents => {
+                self.compile_search();
+                self.rebuild_torrents();
+            }
+            Message::CycleSortColumn => {
+                self.cfg.sort_column = SortColumn::cycle(self.cfg.sort_column);
+                return self.apply_sort();
+            }
+            Message::ToggleSortDirection => {
+                self.cfg.sort_reverse = !self.cfg.sort_reverse;
+                return self.apply_sort();
             }
             Message::DisplayTorrentInfo => {
                 self.torrent_popup = !self.torrent_popup;
             }
             Message::DisplayAddTorrent => {
                 self.add_torrent_popup = !self.add_torrent_popup;
+                // Every torrent starts with the defaults from the config.
+                self.add_options = self.cfg.add_torrent_defaults.clone();
+                self.add_options_focus = false;
+                self.marked_torrent_files.clear();
+                self.torrent_preview = None;
+                self.add_option_field = AddOptionField::default();
                 self.input_mode.toggle_add_torrent();
                 self.reset_cursor();
                 return Some(Message::RefreshTorrents);
             }
+            Message::CloseAddTorrent => {
+                // The popup may have been closed, or never open, while the torrent was being added.
+                if self.input_mode == InputMode::AddTorrent {
+                    return Some(Message::DisplayAddTorrent);
+                }
+                return Some(Message::RefreshTorrents);
+            }
             Message::AddTorrentMagnet => {
-                match self.add_torrent_magnet().await {
-                    Ok(msg) => return Some(msg),
-                    Err(err) => eprintln!("Error adding torrent: {}", err),
-                };
+                if let Err(err) = self.add_torrent_magnet() {
+                    self.notify(NotificationLevel::Error, format!("Failed to add torrent: {}", err));
+                }
+            }
+            Message::PreviewTorrentFile => {
+                if let Err(err) = self.preview_torrent_file() {
+                    self.notify(NotificationLevel::Error, err.to_string());
+                }
             }
             Message::AddTorrentFile => {
-               match self.add_torrent_file().await {
-                    Ok(msg) => return Some(msg),
-                    Err(err) => eprintln!("Error adding torrent: {}", err),
-                };
+                if let Err(err) = self.add_torrent_file() {
+                    self.notify(NotificationLevel::Error, format!("Failed to add torrent: {}", err));
+                }
             }
             Message::DisplayCfgEditor => {
                 self.cfg_popup = !self.cfg_popup;
@@ -74,13 +227,167 @@ impl App {
                 return Some(Message::RefreshTorrents);
             }
             Message::SaveCfg => {
+                // Only reconnect when the server or credentials changed so the session is kept.
+                let reconnect = !self.cfg.same_server(&self.input);
                 self.cfg = self.input.clone();
+                if reconnect {
+                    // The server may have changed so start the sync from scratch.
+                    self.torrent_sync = Default::default();
+                    self.start_worker();
+                }
                 match confy::store("qbtui", None, &self.input) {
-                    Ok(_) => {},
-                    Err(err) => eprintln!("Error creating config file: {}", err),
+                    Ok(_) => self.notify(NotificationLevel::Success, "Config saved"),
+                    Err(err) => self.notify(NotificationLevel::Error, format!("Failed to save config file: {}", err)),
                 }
                 return Some(Message::DisplayCfgEditor);
             }
+            Message::DisplayTorrentLimits => {
+                if !self.torrent_limits_popup && self.selected_hashes().is_empty() {
+                    return None;
+                }
+                self.torrent_limits_popup = !self.torrent_limits_popup;
+                // Start from the limits of the highlighted torrent.
+                if let Some(torrent) = self.state.selected().and_then(|i| self.torrents.get(i)) {
+                    self.torrent_dl_limit_input = self.format_rate_input(torrent.dl_limit.unwrap_or(0));
+                    self.torrent_up_limit_input = self.format_rate_input(torrent.up_limit.unwrap_or(0));
+                    self.ratio_limit_input = match torrent.ratio_limit {
+                        Some(limit) if limit >= 0.0 => limit.to_string(),
+                        Some(-1.0) => "unlimited".to_string(),
+                        _ => "global".to_string(),
+                    };
+                    self.seeding_time_limit_input = match torrent.seeding_time_limit {
+                        Some(limit) if limit >= 0 => limit.to_string(),
+                        Some(-1) => "unlimited".to_string(),
+                        _ => "global".to_string(),
+                    };
+                }
+                self.torrent_limit_field = TorrentLimitField::Download;
+                self.input_mode.toggle_torrent_limits();
+                self.reset_cursor();
+            }
+            Message::SetTorrentLimits => {
+                match self.set_torrent_limits() {
+                    Ok(_) => return Some(Message::DisplayTorrentLimits),
+                    Err(err) => self.notify(NotificationLevel::Error, err.to_string()),
+                }
+            }
+            Message::ToggleAltSpeedLimits => {
+                self.toggle_alt_speed_limits();
+            }
+            Message::DisplaySpeedLimits => {
+                self.speed_limits_popup = !self.speed_limits_popup;
+                // Start from the current limits of the server.
+                let state = self.torrent_sync.server_state();
+                self.download_limit_input = self.format_rate_input(state.dl_rate_limit.unwrap_or(0));
+                self.upload_limit_input = self.format_rate_input(state.up_rate_limit.unwrap_or(0));
+                self.speed_limit_field = SpeedLimitField::Download;
+                self.input_mode.toggle_speed_limits();
+                self.reset_cursor();
+            }
+            Message::SetSpeedLimits => {
+                match self.set_speed_limits() {
+                    Ok(_) => return Some(Message::DisplaySpeedLimits),
+                    Err(err) => self.notify(NotificationLevel::Error, err.to_string()),
+                }
+            }
+            Message::SetFilePriority(priority) => {
+                self.set_file_priority(priority);
+            }
+            Message::DisplayMove => {
+                // Nothing to move, so don't open the popup.
+                if !self.move_popup && self.selected_hashes().is_empty() {
+                    return None;
+                }
+                self.move_popup = !self.move_popup;
+                if self.move_popup {
+                    // Start from the save path of the highlighted torrent, browsing it if it is mounted locally.
+                    let torrent = self.state.selected().and_then(|i| self.torrents.get(i));
+                    self.move_input = torrent.and_then(|torrent| torrent.save_path.clone()).unwrap_or_default();
+                    if let Some(explorer) = self.file_explorer.as_mut()
+                        && std::path::Path::new(&self.move_input).is_dir() {
+                        let _ = explorer.set_cwd(&self.move_input);
+                    }
+                }
+                self.move_browse = false;
+                self.input_mode.toggle_move();
+                self.reset_cursor();
+            }
+            Message::MoveTorrents => {
+                match self.move_torrents() {
+                    Ok(_) => return Some(Message::DisplayMove),
+                    Err(err) => self.notify(NotificationLevel::Error, err.to_string()),
+                }
+            }
+            Message::DisplayCategories => {
+                self.categories_popup = !self.categories_popup;
+                self.categories_state.select(Some(0));
+                self.category_form = None;
+                self.category_remove_pending = None;
+                self.input_mode.toggle_categories();
+                self.reset_cursor();
+                return Some(Message::RefreshTorrents);
+            }
+            Message::SaveCategory => {
+                match self.save_category() {
+                    Ok(_) => self.category_form = None,
+                    Err(err) => self.notify(NotificationLevel::Error, err.to_string()),
+                }
+            }
+            Message::RemoveCategory => {
+                self.remove_category();
+            }
+            Message::DisplayCategoryPicker => {
+                // Nothing to set the category of, so don't open the picker.
+                if !self.category_picker_popup && self.selected_hashes().is_empty() {
+                    return None;
+                }
+                self.category_picker_popup = !self.category_picker_popup;
+                // Start from the category of the highlighted torrent.
+                let category = self.state.selected()
+                    .and_then(|i| self.torrents.get(i))
+                    .and_then(|torrent| torrent.category.clone())
+                    .unwrap_or_default();
+                let position = self.sorted_categories().iter().position(|c| c.name == category);
+                self.category_picker_state.select(Some(position.map_or(0, |i| i + 1)));
+                self.input_mode.toggle_category_picker();
+            }
+            Message::SetCategory => {
+                self.set_category();
+                return Some(Message::DisplayCategoryPicker);
+            }
+            Message::ToggleCategoryColumn => {
+                self.cfg.show_category_column = !self.cfg.show_category_column;
+                // Keep the config editor input in sync so saving it doesn't revert the column.
+                self.input.show_category_column = self.cfg.show_category_column;
+                if let Err(err) = confy::store("qbtui", None, &self.cfg) {
+                    self.notify(NotificationLevel::Error, format!("Failed to save the table columns to config file: {}", err));
+                }
+            }
+            Message::DisplayRename => {
+                if !self.rename_popup {
+                    // Nothing to rename, so don't open the prompt.
+                    let (target, name) = self.rename_target()?;
+                    self.rename_target = Some(target);
+                    self.rename_input = name;
+                }
+                self.rename_popup = !self.rename_popup;
+                self.input_mode.toggle_rename();
+                self.reset_cursor();
+            }
+            Message::Rename => {
+                match self.rename() {
+                    Ok(_) => return Some(Message::DisplayRename),
+                    Err(err) => self.notify(NotificationLevel::Error, err.to_string()),
+                }
+            }
+            Message::DisplayNotifications => {
+                self.notifications_popup = !self.notifications_popup;
+                self.notifications_scroll = 0;
+                self.input_mode.toggle_notifications();
+            }
+            Message::Api(response) => {
+                return self.handle_api_response(*response);
+            }
             // Set running to false to quit the application.
             Message::Quit => {
                 self.running = false;
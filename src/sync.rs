@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use qbit_rs::model::{Category, PeerSyncData, SyncData, Torrent};
+use serde::Deserialize;
+
+/// Overwrite the fields of a target with the fields present in a partial update.
+/// The qBittorrent sync endpoints only send the fields that changed since the last response.
+macro_rules! merge_fields {
+    ($target:expr, $update:expr, [$($field:ident),* $(,)?]) => {{
+        $(
+            if $update.$field.is_some() {
+                $target.$field = $update.$field;
+            }
+        )*
+    }};
+}
+
+/// Local copy of the torrent list kept up to date with /api/v2/sync/maindata.
+#[derive(Debug, Default)]
+pub struct TorrentSync {
+    /// Response ID of the last applied sync response.
+    rid: i64,
+    /// Torrents keyed by their info hash.
+    torrents: HashMap<String, Torrent>,
+    /// Categories keyed by their name.
+    categories: HashMap<String, Category>,
+    tags: Vec<String>,
+    /// Torrent hashes keyed by tracker URL.
+    trackers: HashMap<String, Vec<String>>,
+    server_state: ServerState,
+}
+
+/// Global transfer info and state of the server sent as server_state by /api/v2/sync/maindata.
+/// Rates are in bytes/s, a limit of 0 is unlimited.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct ServerState {
+    pub dl_info_speed: Option<i64>,
+    pub dl_info_data: Option<i64>,
+    pub up_info_speed: Option<i64>,
+    pub up_info_data: Option<i64>,
+    pub dl_rate_limit: Option<i64>,
+    pub up_rate_limit: Option<i64>,
+    pub dht_nodes: Option<i64>,
+    /// One of connected, firewalled or disconnected.
+    pub connection_status: Option<String>,
+    pub use_alt_speed_limits: Option<bool>,
+    pub free_space_on_disk: Option<i64>,
+}
+
+impl TorrentSync {
+    /// Response ID to send with the next sync request.
+    pub fn rid(&self) -> i64 {
+        self.rid
+    }
+
+    /// Applies a sync response, replacing the local map on a full update
+    /// and merging the changed fields otherwise.
+    pub fn apply(&mut self, data: SyncData) {
+        if data.full_update.unwrap_or(false) {
+            self.torrents.clear();
+            self.categories.clear();
+            self.tags.clear();
+            self.trackers.clear();
+            self.server_state = ServerState::default();
+        }
+        if let Some(torrents) = data.torrents {
+            for (hash, update) in torrents {
+                match self.torrents.get_mut(&hash) {
+                    Some(torrent) => merge_torrent(torrent, update),
+                    None => {
+                        let mut torrent = update;
+                        // The sync endpoint keys torrents by hash instead of including it.
+                        torrent.hash = Some(hash.clone());
+                        self.torrents.insert(hash, torrent);
+                    }
+                }
+            }
+        }
+        if let Some(removed) = data.torrents_removed {
+            for hash in removed.iter() {
+                self.torrents.remove(hash);
+            }
+            // The server doesn't resend the tracker lists for removed torrents, so drop them here along with emptied trackers.
+            self.trackers.retain(|_, hashes| {
+                hashes.retain(|hash| !removed.contains(hash));
+                !hashes.is_empty()
+            });
+        }
+        if let Some(categories) = data.categories {
+            self.categories.extend(categories);
+        }
+        if let Some(removed) = data.categories_removed {
+            for category in removed {
+                self.categories.remove(&category);
+            }
+        }
+        if let Some(tags) = data.tags {
+            for tag in tags {
+                if !self.tags.contains(&tag) {
+                    self.tags.push(tag);
+                }
+            }
+            self.tags.sort();
+        }
+        if let Some(removed) = data.tags_removed {
+            self.tags.retain(|tag| !removed.contains(tag));
+        }
+        if let Some(trackers) = data.trackers {
+            self.trackers.extend(trackers);
+        }
+        if let Some(removed) = data.trackers_removed {
+            for tracker in removed {
+                self.trackers.remove(&tracker);
+            }
+        }
+        if let Some(server_state) = data.server_state {
+            // Skip a server state that doesn't match the expected types instead of failing the sync.
+            let update = serde_value::to_value(server_state)
+                .ok()
+                .and_then(|value| value.deserialize_into::<ServerState>().ok());
+            if let Some(update) = update {
+                merge_fields!(self.server_state, update, [
+                    dl_info_speed, dl_info_data, up_info_speed, up_info_data, dl_rate_limit,
+                    up_rate_limit, dht_nodes, connection_status, use_alt_speed_limits, free_space_on_disk,
+                ]);
+            }
+        }
+        self.rid = data.rid;
+    }
+
+    /// Global transfer info and state of the server.
+    pub fn server_state(&self) -> &ServerState {
+        &self.server_state
+    }
+
+    /// Categories on the server keyed by their name.
+    pub fn categories(&self) -> &HashMap<String, Category> {
+        &self.categories
+    }
+
+    /// Tags on the server in alphabetical order.
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Torrent hashes keyed by tracker URL.
+    pub fn trackers(&self) -> &HashMap<String, Vec<String>> {
+        &self.trackers
+    }
+
+    /// Returns true if a torrent with the hash is on the server.
+    pub fn contains(&self, hash: &str) -> bool {
+        self.torrents.contains_key(hash)
+    }
+
+    /// Number of torrents on the server.
+    pub fn len(&self) -> usize {
+        self.torrents.len()
+    }
+
+    /// Iterate over the torrents in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &Torrent> {
+        self.torrents.values()
+    }
+
+    /// Returns the torrents ordered by the time they were added.
+    pub fn torrents(&self) -> Vec<Torrent> {
+        let mut torrents: Vec<Torrent> = self.torrents.values().cloned().collect();
+        torrents.sort_by(|a, b| a.added_on.cmp(&b.added_on).then_with(|| a.hash.cmp(&b.hash)));
+        torrents
+    }
+}
+
+/// Merge a partial torrent update from the sync endpoint into an existing torrent.
+fn merge_torrent(torrent: &mut Torrent, update: Torrent) {
+    merge_fields!(torrent, update, [
+        added_on, amount_left, auto_tmm, availability, category, completed, completion_on,
+        content_path, dl_limit, dlspeed, downloaded, downloaded_session, eta, f_l_piece_prio,
+        force_start, last_activity, magnet_uri, max_ratio, max_seeding_time, name,
+        num_complete, num_incomplete, num_leechs, num_seeds, priority, progress, ratio,
+        ratio_limit, save_path, seeding_time, seeding_time_limit, seen_complete, seq_dl, size,
+        state, super_seeding, tags, time_active, total_size, tracker, up_limit, uploaded,
+        uploaded_session, upspeed,
+    ]);
+}
+
+/// Merge a /api/v2/sync/torrentPeers response into the currently displayed peers.
+/// A full update replaces the existing peers.
+pub fn merge_peers(existing: &mut PeerSyncData, update: PeerSyncData) {
+    if update.full_update.unwrap_or(false) || existing.peers.is_none() {
+        *existing = update;
+        return;
+    }
+    let peers = existing.peers.get_or_insert_with(HashMap::new);
+    if let Some(updated) = update.peers {
+        for (addr, update) in updated {
+            match peers.get_mut(&addr) {
+                Some(peer) => merge_fields!(peer, update, [
+                    client, connection, country, country_code, dl_speed, downloaded, files,
+                    flags, flags_desc, ip, port, progress, relevance, up_speed, uploaded,
+                ]),
+                None => {
+                    peers.insert(addr, update);
+                }
+            }
+        }
+    }
+    if let Some(removed) = update.peers_removed {
+        for addr in removed {
+            peers.remove(&addr);
+        }
+    }
+    existing.rid = update.rid;
+    existing.show_flags = update.show_flags;
+}
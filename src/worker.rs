@@ -0,0 +1,373 @@
+use crate::AppConfig;
+use qbit_rs::{
+    model::{
+        AddTorrentArg, Credential, NonEmptyStr, PeerSyncData, Priority, RatioLimit, SeedingTimeLimit, SetTorrentSharedLimitArg,
+        SyncData, TorrentContent, Tracker
+    },
+    ApiError, Qbit};
+use std::{sync::Arc, time::Duration};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+type ApiResult<T> = Result<T, qbit_rs::Error>;
+
+/// Number of times to retry setting the file priorities of a torrent that was just added.
+const SKIP_FILES_ATTEMPTS: usize = 10;
+
+/// Requests handled by the [`ApiWorker`].
+#[derive(Debug)]
+pub enum ApiRequest {
+    /// Sync the torrent list from the given response ID.
+    Sync { rid: i64 },
+    /// Get the qBittorrent and WebAPI versions of the server.
+    Version,
+    TorrentContents { hash: String },
+    TorrentTrackers { hash: String },
+    TorrentPeers { hash: String, rid: Option<i64> },
+    Pause { hashes: Vec<String> },
+    Resume { hashes: Vec<String> },
+    ForceStart { hashes: Vec<String>, value: bool },
+    Recheck { hashes: Vec<String> },
+    Reannounce { hashes: Vec<String> },
+    Delete { hashes: Vec<String>, delete_files: bool },
+    /// Add torrents, with their names and the names of those skipped before sending for the summary.
+    /// Unwanted has the info-hash and indexes of files to not download once the torrent is added.
+    AddTorrent {
+        arg: Box<AddTorrentArg>,
+        names: Vec<String>,
+        skipped: Vec<String>,
+        unwanted: Option<(String, Vec<i64>)>,
+    },
+    ToggleAltSpeedLimits,
+    /// Set the download and upload limits in bytes/s and share limits of torrents, a rate of 0 is no limit.
+    SetTorrentLimits {
+        hashes: Vec<String>,
+        download: u64,
+        upload: u64,
+        ratio: RatioLimit,
+        seeding_time: SeedingTimeLimit,
+    },
+    /// Set the global download and upload limits in bytes/s, 0 is no limit.
+    SetSpeedLimits { download: u64, upload: u64 },
+    /// Set the priority of files in a torrent by their index.
+    SetFilePriority { hash: String, indexes: Vec<i64>, priority: Priority },
+    /// Move the data of torrents to a new save path on the server.
+    SetLocation { hashes: Vec<String>, location: String },
+    RenameTorrent { hash: String, name: String },
+    /// Rename a file or folder of a torrent, paths are relative to the torrent's save path.
+    RenameFile { hash: String, old_path: String, new_path: String },
+    RenameFolder { hash: String, old_path: String, new_path: String },
+    /// Set the category of torrents, an empty category removes it.
+    SetCategory { hashes: Vec<String>, category: String },
+    /// Add a category or change the save path of an existing one, an empty save path uses the default.
+    AddCategory { name: String, save_path: String },
+    EditCategory { name: String, save_path: String },
+    RemoveCategories { names: Vec<String> },
+}
+
+impl ApiRequest {
+    /// The response for this request when it couldn't be sent.
+    fn failed(self, err: qbit_rs::Error) -> ApiResponse {
+        match self {
+            ApiRequest::Sync { .. } => ApiResponse::Synced(Err(err)),
+            ApiRequest::Version => ApiResponse::Version(Err(err)),
+            ApiRequest::TorrentContents { hash } => ApiResponse::TorrentContents { hash, result: Err(err) },
+            ApiRequest::TorrentTrackers { hash } => ApiResponse::TorrentTrackers { hash, result: Err(err) },
+            ApiRequest::TorrentPeers { hash, rid } => ApiResponse::TorrentPeers { hash, rid, result: Err(err) },
+            ApiRequest::AddTorrent { names, skipped, .. } => ApiResponse::TorrentAdded { names, skipped, result: Err(err) },
+            ApiRequest::Pause { .. } => action_failed("pause torrents", err),
+            ApiRequest::Resume { .. } => action_failed("resume torrents", err),
+            ApiRequest::ForceStart { .. } => action_failed("set force start", err),
+            ApiRequest::Recheck { .. } => action_failed("recheck torrents", err),
+            ApiRequest::Reannounce { .. } => action_failed("reannounce torrents", err),
+            ApiRequest::Delete { .. } => action_failed("delete torrents", err),
+            ApiRequest::SetTorrentLimits { .. } => action_failed("set torrent limits", err),
+            ApiRequest::ToggleAltSpeedLimits => action_failed("toggle alternative speed limits", err),
+            ApiRequest::SetSpeedLimits { .. } => action_failed("set the global speed limits", err),
+            ApiRequest::SetFilePriority { .. } => action_failed("set file priority", err),
+            ApiRequest::SetLocation { .. } => action_failed("move torrents", err),
+            ApiRequest::RenameTorrent { .. } => action_failed("rename the torrent", err),
+            ApiRequest::RenameFile { .. } => action_failed("rename the file", err),
+            ApiRequest::RenameFolder { .. } => action_failed("rename the folder", err),
+            ApiRequest::SetCategory { .. } => action_failed("set the category", err),
+            ApiRequest::AddCategory { .. } => action_failed("add the category", err),
+            ApiRequest::EditCategory { .. } => action_failed("edit the category", err),
+            ApiRequest::RemoveCategories { .. } => action_failed("remove the category", err),
+        }
+    }
+}
+
+/// Results sent back by the [`ApiWorker`], one for each [`ApiRequest`].
+#[derive(Debug)]
+pub enum ApiResponse {
+    Synced(ApiResult<SyncData>),
+    /// The qBittorrent and WebAPI versions of the server.
+    Version(ApiResult<(String, String)>),
+    TorrentContents { hash: String, result: ApiResult<Vec<TorrentContent>> },
+    TorrentTrackers { hash: String, result: ApiResult<Vec<Tracker>> },
+    TorrentPeers { hash: String, rid: Option<i64>, result: ApiResult<PeerSyncData> },
+    /// Result of an action on torrents such as pausing or deleting them.
+    /// Includes a message for when the action succeeded and a description of the action for when it failed.
+    ActionDone { done: String, failed: &'static str, result: ApiResult<()> },
+    TorrentAdded { names: Vec<String>, skipped: Vec<String>, result: ApiResult<()> },
+}
+
+/// Background task that sends requests to the qBittorrent API.
+/// Requests are handled concurrently so a slow server never blocks drawing or input.
+#[derive(Debug)]
+pub struct ApiWorker {
+    requests: UnboundedSender<ApiRequest>,
+    responses: UnboundedReceiver<ApiResponse>,
+}
+
+impl ApiWorker {
+    /// Spawn the worker task for the server in the given config.
+    /// A single authenticated session is shared by every request and is only
+    /// logged in again when the server rejects the cookie.
+    /// The task stops when the worker is dropped.
+    pub fn spawn(cfg: AppConfig) -> Self {
+        let (request_tx, mut request_rx) = mpsc::unbounded_channel::<ApiRequest>();
+        let (response_tx, response_rx) = mpsc::unbounded_channel();
+        let api = Arc::new(api(&cfg));
+        tokio::spawn(async move {
+            while let Some(request) = request_rx.recv().await {
+                // Log in before handing out the session so concurrent requests don't each log in.
+                // A failed login fails the request instead of letting it retry the login again.
+                if api.get_cookie().await.is_none()
+                    && let Err(err) = api.login(false).await {
+                    let _ = response_tx.send(request.failed(err));
+                    continue;
+                }
+                let api = Arc::clone(&api);
+                let responses = response_tx.clone();
+                tokio::spawn(async move {
+                    // The receiver is gone once the worker is replaced, so the result is dropped.
+                    let _ = responses.send(handle(&api, request).await);
+                });
+            }
+        });
+        Self {
+            requests: request_tx,
+            responses: response_rx,
+        }
+    }
+
+    /// Queue a request for the worker.
+    pub fn send(&self, request: ApiRequest) {
+        let _ = self.requests.send(request);
+    }
+
+    /// Wait for the next response from the worker.
+    pub async fn recv(&mut self) -> Option<ApiResponse> {
+        self.responses.recv().await
+    }
+}
+
+fn api(cfg: &AppConfig) -> Qbit {
+    let credential =  Credential::new(&cfg.username, &cfg.password);
+    let url: &str = &cfg.api_url;
+    Qbit::new(url, credential)
+}
+
+/// The response for a torrent action that couldn't be sent.
+fn action_failed(failed: &'static str, err: qbit_rs::Error) -> ApiResponse {
+    ApiResponse::ActionDone { done: String::new(), failed, result: Err(err) }
+}
+
+/// Returns "1 torrent" or "n torrents".
+fn torrent_count(count: usize) -> String {
+    if count == 1 {
+        "1 torrent".to_string()
+    } else {
+        format!("{} torrents", count)
+    }
+}
+
+/// Returns "1 file" or "n files".
+fn file_count(count: usize) -> String {
+    if count == 1 {
+        "1 file".to_string()
+    } else {
+        format!("{} files", count)
+    }
+}
+
+/// Describe a file priority for notifications.
+fn priority_label(priority: Priority) -> &'static str {
+    match priority {
+        Priority::DoNotDownload => "do not download",
+        Priority::Normal => "normal priority",
+        Priority::Mixed => "mixed priority",
+        Priority::High => "high priority",
+        Priority::Maximal => "maximal priority",
+    }
+}
+
+/// Set the rate and share limits of torrents, stopping at the first error.
+async fn set_torrent_limits(
+    api: &Qbit,
+    hashes: Vec<String>,
+    download: u64,
+    upload: u64,
+    ratio: RatioLimit,
+    seeding_time: SeedingTimeLimit,
+) -> ApiResult<()> {
+    api.set_torrent_download_limit(hashes.clone(), download).await?;
+    api.set_torrent_upload_limit(hashes.clone(), upload).await?;
+    api.set_torrent_shared_limit(SetTorrentSharedLimitArg {
+        hashes: hashes.into(),
+        ratio_limit: Some(ratio),
+        seeding_time_limit: Some(seeding_time),
+    }).await
+}
+
+/// Set files of a torrent that was just added to not be downloaded.
+/// qBittorrent adds torrents in the background so this is retried until it knows the torrent.
+async fn skip_files(api: &Qbit, hash: &str, indexes: Vec<i64>) -> ApiResult<()> {
+    let mut attempts = 0;
+    loop {
+        match api.set_file_priority(hash, indexes.clone(), Priority::DoNotDownload).await {
+            Ok(_) => return Ok(()),
+            Err(err) if attempts >= SKIP_FILES_ATTEMPTS => return Err(err),
+            Err(_) => {
+                attempts += 1;
+                tokio::time::sleep(Duration::from_millis(300)).await;
+            },
+        }
+    }
+}
+
+/// Send a request to the API and wrap the result in its response.
+async fn handle(api: &Qbit, request: ApiRequest) -> ApiResponse {
+    match request {
+        ApiRequest::Sync { rid } => ApiResponse::Synced(api.sync(rid).await),
+        ApiRequest::Version => {
+            let result = tokio::try_join!(api.get_version(), api.get_webapi_version());
+            ApiResponse::Version(result)
+        },
+        ApiRequest::TorrentContents { hash } => {
+            let result = api.get_torrent_contents(&hash, None).await;
+            ApiResponse::TorrentContents { hash, result }
+        },
+        ApiRequest::TorrentTrackers { hash } => {
+            let result = api.get_torrent_trackers(&hash).await;
+            ApiResponse::TorrentTrackers { hash, result }
+        },
+        ApiRequest::TorrentPeers { hash, rid } => {
+            let result = api.get_torrent_peers(&hash, rid).await;
+            ApiResponse::TorrentPeers { hash, rid, result }
+        },
+        ApiRequest::Pause { hashes } => {
+            let done = format!("Paused {}", torrent_count(hashes.len()));
+            ApiResponse::ActionDone { done, failed: "pause torrents", result: api.pause_torrents(hashes).await }
+        },
+        ApiRequest::Resume { hashes } => {
+            let done = format!("Resumed {}", torrent_count(hashes.len()));
+            ApiResponse::ActionDone { done, failed: "resume torrents", result: api.resume_torrents(hashes).await }
+        },
+        ApiRequest::ForceStart { hashes, value } => {
+            let done = format!("Force start {} for {}", if value { "enabled" } else { "disabled" }, torrent_count(hashes.len()));
+            ApiResponse::ActionDone { done, failed: "set force start", result: api.set_force_start(hashes, value).await }
+        },
+        ApiRequest::Recheck { hashes } => {
+            let done = format!("Rechecking {}", torrent_count(hashes.len()));
+            ApiResponse::ActionDone { done, failed: "recheck torrents", result: api.recheck_torrents(hashes).await }
+        },
+        ApiRequest::Reannounce { hashes } => {
+            let done = format!("Reannounced {}", torrent_count(hashes.len()));
+            ApiResponse::ActionDone { done, failed: "reannounce torrents", result: api.reannounce_torrents(hashes).await }
+        },
+        ApiRequest::Delete { hashes, delete_files } => {
+            let done = format!("Deleted {}", torrent_count(hashes.len()));
+            let result = api.delete_torrents(hashes, delete_files).await;
+            ApiResponse::ActionDone { done, failed: "delete torrents", result }
+        },
+        ApiRequest::AddTorrent { arg, names, mut skipped, unwanted } => {
+            let result = api.add_torrent(arg.as_ref()).await;
+            if result.is_ok()
+                && let Some((hash, indexes)) = unwanted
+                && skip_files(api, &hash, indexes).await.is_err() {
+                skipped.extend(names.iter().map(|name| format!("{} (failed to skip unticked files)", name)));
+            }
+            ApiResponse::TorrentAdded { names, skipped, result }
+        },
+        ApiRequest::SetTorrentLimits { hashes, download, upload, ratio, seeding_time } => {
+            let done = format!("Set limits for {}", torrent_count(hashes.len()));
+            let result = set_torrent_limits(api, hashes, download, upload, ratio, seeding_time).await;
+            ApiResponse::ActionDone { done, failed: "set torrent limits", result }
+        },
+        ApiRequest::ToggleAltSpeedLimits => {
+            let done = "Toggled alternative speed limits".to_string();
+            let result = api.toggle_speed_limits_mode().await;
+            ApiResponse::ActionDone { done, failed: "toggle alternative speed limits", result }
+        },
+        ApiRequest::SetSpeedLimits { download, upload } => {
+            let done = "Global speed limits set".to_string();
+            let result = match api.set_download_limit(download).await {
+                Ok(_) => api.set_upload_limit(upload).await,
+                Err(err) => Err(err),
+            };
+            ApiResponse::ActionDone { done, failed: "set the global speed limits", result }
+        },
+        ApiRequest::SetFilePriority { hash, indexes, priority } => {
+            let done = format!("Set {} to {}", file_count(indexes.len()), priority_label(priority));
+            let result = api.set_file_priority(hash, indexes, priority).await;
+            ApiResponse::ActionDone { done, failed: "set file priority", result }
+        },
+        ApiRequest::SetLocation { hashes, location } => {
+            let done = format!("Moving {} to {}", torrent_count(hashes.len()), location);
+            let result = api.set_torrent_location(hashes, &location).await;
+            ApiResponse::ActionDone { done, failed: "move torrents", result }
+        },
+        ApiRequest::RenameTorrent { hash, name } => {
+            let done = format!("Renamed the torrent to {}", name);
+            let result = match NonEmptyStr::new(name) {
+                Some(name) => api.set_torrent_name(hash, name).await,
+                None => Err(qbit_rs::Error::ApiError(ApiError::TorrentNameEmpty)),
+            };
+            ApiResponse::ActionDone { done, failed: "rename the torrent", result }
+        },
+        ApiRequest::RenameFile { hash, old_path, new_path } => {
+            let done = format!("Renamed the file to {}", new_path);
+            let result = api.rename_file(hash, old_path, &new_path).await;
+            ApiResponse::ActionDone { done, failed: "rename the file", result }
+        },
+        ApiRequest::RenameFolder { hash, old_path, new_path } => {
+            let done = format!("Renamed the folder to {}", new_path);
+            let result = api.rename_folder(hash, old_path, &new_path).await;
+            ApiResponse::ActionDone { done, failed: "rename the folder", result }
+        },
+        ApiRequest::SetCategory { hashes, category } => {
+            let done = if category.is_empty() {
+                format!("Removed the category of {}", torrent_count(hashes.len()))
+            } else {
+                format!("Set the category of {} to {}", torrent_count(hashes.len()), category)
+            };
+            let result = api.set_torrent_category(hashes, category).await;
+            ApiResponse::ActionDone { done, failed: "set the category", result }
+        },
+        ApiRequest::AddCategory { name, save_path } => {
+            let done = format!("Added the category {}", name);
+            let result = match NonEmptyStr::new(name) {
+                Some(name) => api.add_category(name, save_path).await,
+                None => Err(qbit_rs::Error::ApiError(ApiError::CategoryEditingFailed)),
+            };
+            ApiResponse::ActionDone { done, failed: "add the category", result }
+        },
+        ApiRequest::EditCategory { name, save_path } => {
+            let done = format!("Edited the category {}", name);
+            let result = match NonEmptyStr::new(name) {
+                Some(name) => api.edit_category(name, save_path).await,
+                None => Err(qbit_rs::Error::ApiError(ApiError::CategoryNotFound)),
+            };
+            ApiResponse::ActionDone { done, failed: "edit the category", result }
+        },
+        ApiRequest::RemoveCategories { names } => {
+            let done = match names.as_slice() {
+                [name] => format!("Removed the category {}", name),
+                _ => format!("Removed {} categories", names.len()),
+            };
+            let result = api.remove_categories(names).await;
+            ApiResponse::ActionDone { done, failed: "remove the category", result }
+        },
+    }
+}